@@ -0,0 +1,493 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use crate::data_structures::graph::Graph;
+use crate::karger_stein::KargerSteinAlgo;
+use crate::min_cut_algo::{MinCutEnumeration, MinCutEnumerator, MinCutError, MinCutResult};
+use crate::traits::EdgeWeightedGraph;
+use crate::weight::Weight;
+
+// A cactus representing the min cuts of a graph: every min cut removes either a tree edge of the
+// cactus or two edges of one of its cycles, and every such removal gives a min cut. Vertices that
+// no min cut separates share a node, and some nodes hold no vertex at all.
+pub struct Cactus<'a, W = usize> {
+    graph: &'a Graph<W>,
+    value: W,
+    node_of: Box<[usize]>,
+    adj: Box<[Vec<(usize, usize)>]>,
+    edges: Box<[(usize, usize)]>,
+    tree_edges: Box<[usize]>,
+    cycles: Box<[Box<[usize]>]>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Region {
+    Root,
+    Tree,
+    CyclePart,
+    // The union of the parts of a cycle but the one holding vertex 0. It is a region of the
+    // node the cycle hangs from, not a node of its own.
+    CycleHub,
+}
+
+impl<'a, W: Weight> Cactus<'a, W> {
+    // Builds the cactus from the min cuts enumerated by Karger-Stein, which finds all of them with
+    // probability at least 1 - 1/|V|. Fails for graphs of fewer than 2 vertices, for those whose
+    // min cut weighs 0, such as disconnected graphs, and with `IncompleteMinCuts` when the min cuts
+    // missed leave the others without a cactus.
+    pub fn new(graph: &'a Graph<W>) -> Result<Cactus<'a, W>, MinCutError> {
+        Self::from_karger_stein(graph, KargerSteinAlgo::new(graph))
+    }
+
+    pub fn with_seed(graph: &'a Graph<W>, seed: u64) -> Result<Cactus<'a, W>, MinCutError> {
+        Self::from_karger_stein(graph, KargerSteinAlgo::with_seed(graph, seed))
+    }
+
+    fn from_karger_stein(
+        graph: &'a Graph<W>,
+        karger_stein: KargerSteinAlgo<10, W>,
+    ) -> Result<Cactus<'a, W>, MinCutError> {
+        let min_cuts = karger_stein.enumerate_min_cuts(Self::prob(graph), false)?;
+        Self::from_min_cuts(graph, &min_cuts)
+    }
+
+    fn prob(graph: &Graph<W>) -> f64 {
+        1f64 - 1f64 / graph.num_vertices() as f64
+    }
+
+    // Builds the cactus in time about |V| times the number of min cuts. The min cuts need not all
+    // be listed, but those missing must leave the others a cactus, and otherwise it fails with
+    // `IncompleteMinCuts`.
+    pub fn from_min_cuts(
+        graph: &'a Graph<W>,
+        min_cuts: &MinCutEnumeration<W>,
+    ) -> Result<Cactus<'a, W>, MinCutError> {
+        let n = graph.num_vertices();
+        if n < 2 {
+            return Err(MinCutError::TooFewVertices { num_vertices: n });
+        }
+        if min_cuts.value() <= W::ZERO {
+            return Err(MinCutError::ZeroMinCut);
+        }
+
+        let mut atom_ids = HashMap::new();
+        let atom_of = (0..n)
+            .map(|v| {
+                let signature = min_cuts
+                    .cuts()
+                    .iter()
+                    .map(|cut| cut.partition().0.binary_search(&v).is_ok())
+                    .collect::<Vec<_>>();
+                let next = atom_ids.len();
+                *atom_ids.entry(signature).or_insert(next)
+            })
+            .collect::<Box<[usize]>>();
+        let num_atoms = atom_ids.len();
+
+        // Every cut as the set of atoms on the side without vertex 0.
+        let sets = min_cuts
+            .cuts()
+            .iter()
+            .map(|cut| {
+                let mut set = vec![false; num_atoms];
+                for &v in cut.partition().1 {
+                    set[atom_of[v]] = true;
+                }
+                set
+            })
+            .collect::<Vec<_>>();
+        let cut_index = sets
+            .iter()
+            .enumerate()
+            .map(|(i, set)| (set, i))
+            .collect::<HashMap<_, _>>();
+        let crossing = crossing_sets(&sets);
+
+        // The cuts crossing no other one are laminar, and each is nested in the smallest of them
+        // containing it. The parts of a cycle are nested in the union of its parts.
+        let mut regions = vec![(vec![true; num_atoms], Region::Root)];
+        regions.extend(
+            sets.iter()
+                .zip(crossing.iter())
+                .filter(|(_, &crossing)| !crossing)
+                .map(|(set, _)| (set.clone(), Region::Tree)),
+        );
+        let size = |set: &[bool]| set.iter().filter(|&&b| b).count();
+        let mut sorted = (0..regions.len()).collect::<Vec<_>>();
+        sorted.sort_by_key(|&i| std::cmp::Reverse(size(&regions[i].0)));
+        let mut parent = vec![0; regions.len()];
+        let mut children = vec![Vec::new(); regions.len()];
+        let mut owner = vec![0; num_atoms];
+        for &i in sorted.iter().skip(1) {
+            let atoms = members(&regions[i].0).collect::<Vec<_>>();
+            parent[i] = owner[atoms[0]];
+            if atoms.iter().any(|&a| owner[a] != parent[i]) {
+                return Err(MinCutError::IncompleteMinCuts);
+            }
+            for &a in atoms.iter() {
+                owner[a] = i;
+            }
+            children[parent[i]].push(i);
+        }
+
+        // A region whose children pair up into cuts is the union of the parts of a cycle, and the
+        // pairs are the arcs of consecutive parts. Its region is not a node of its own.
+        let union = |regions: &[(Vec<bool>, Region)], parts: &[usize]| {
+            let mut set = vec![false; num_atoms];
+            for &p in parts {
+                for a in members(&regions[p].0) {
+                    set[a] = true;
+                }
+            }
+            set
+        };
+        let mut cycles = Vec::new();
+        for i in 1..regions.len() {
+            let parts = &children[i];
+            if parts.len() < 3 {
+                continue;
+            }
+            // Every part of a cycle pairs up with a neighbour, so a region whose first child pairs
+            // with none is not checked further.
+            let pairs = |x: usize| {
+                (0..parts.len())
+                    .filter(|&y| y != x)
+                    .filter(|&y| cut_index.contains_key(&union(&regions, &[parts[x], parts[y]])))
+                    .collect::<Vec<_>>()
+            };
+            if pairs(0).is_empty() {
+                continue;
+            }
+            let adjacent = (0..parts.len()).map(pairs).collect::<Vec<_>>();
+
+            let own_atoms = size(&regions[i].0) - size(&union(&regions, parts));
+            let mut order = adjacent
+                .iter()
+                .position(|next| next.len() == 1)
+                .into_iter()
+                .collect::<Vec<_>>();
+            while let Some(next) = order.last().and_then(|&last| {
+                adjacent[last]
+                    .iter()
+                    .copied()
+                    .find(|next| !order.contains(next))
+            }) {
+                order.push(next);
+            }
+            if own_atoms > 0
+                || order.len() < parts.len()
+                || adjacent.iter().any(|next| next.len() > 2)
+            {
+                return Err(MinCutError::IncompleteMinCuts);
+            }
+
+            // The union of the parts of a cycle through an empty part of another cycle is that part.
+            if regions[i].1 == Region::Tree {
+                regions[i].1 = Region::CycleHub;
+            }
+            let mut cycle = vec![i];
+            for &x in order.iter() {
+                regions[parts[x]].1 = Region::CyclePart;
+                cycle.push(parts[x]);
+            }
+            cycles.push(cycle);
+        }
+
+        // Every crossing cut is an arc of consecutive parts of a cycle.
+        let mut explained = 0;
+        for cycle in cycles.iter() {
+            let parts = &cycle[1..];
+            for start in 0..parts.len() {
+                let mut arc = vec![false; num_atoms];
+                for end in start..parts.len() {
+                    for a in members(&regions[parts[end]].0) {
+                        arc[a] = true;
+                    }
+                    let len = end - start + 1;
+                    if len > 1
+                        && len < parts.len()
+                        && cut_index.get(&arc).is_some_and(|&j| crossing[j])
+                    {
+                        explained += 1;
+                    }
+                }
+            }
+        }
+        if explained < crossing.iter().filter(|&&crossing| crossing).count() {
+            return Err(MinCutError::IncompleteMinCuts);
+        }
+
+        // Each region but the hubs is a node holding the atoms of no smaller region.
+        let mut node = vec![0; regions.len()];
+        let mut atom_node = vec![0; num_atoms];
+        let mut num_nodes = 0;
+        for &i in sorted.iter() {
+            if regions[i].1 == Region::CycleHub {
+                node[i] = node[parent[i]];
+            } else {
+                node[i] = num_nodes;
+                num_nodes += 1;
+                for a in members(&regions[i].0) {
+                    atom_node[a] = node[i];
+                }
+            }
+        }
+
+        let mut edges = Vec::new();
+        let mut tree_edges = Vec::new();
+        for (i, (_, region)) in regions.iter().enumerate() {
+            if *region == Region::Tree {
+                tree_edges.push(edges.len());
+                edges.push((node[parent[i]], node[i]));
+            }
+        }
+        let cycles = cycles
+            .iter()
+            .map(|cycle| {
+                (0..cycle.len())
+                    .map(|i| {
+                        edges.push((node[cycle[i]], node[cycle[(i + 1) % cycle.len()]]));
+                        edges.len() - 1
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let mut adj = vec![Vec::new(); num_nodes].into_boxed_slice();
+        for (e, &(x, y)) in edges.iter().enumerate() {
+            adj[x].push((y, e));
+            adj[y].push((x, e));
+        }
+
+        Ok(Cactus {
+            graph,
+            value: min_cuts.value(),
+            node_of: atom_of.iter().map(|&a| atom_node[a]).collect(),
+            adj,
+            edges: edges.into_boxed_slice(),
+            tree_edges: tree_edges.into_boxed_slice(),
+            cycles,
+        })
+    }
+
+    pub fn value(&self) -> W {
+        self.value
+    }
+
+    pub fn num_nodes(&self) -> usize {
+        self.adj.len()
+    }
+
+    pub fn node(&self, v: usize) -> usize {
+        self.node_of[v]
+    }
+
+    pub fn separates(&self, u: usize, v: usize) -> bool {
+        self.node_of[u] != self.node_of[v]
+    }
+
+    pub fn num_cuts(&self) -> usize {
+        let cycle_cuts = self
+            .cycles
+            .iter()
+            .map(|cycle| cycle.len() * (cycle.len() - 1) / 2);
+        self.tree_edges.len() + cycle_cuts.sum::<usize>()
+    }
+
+    pub fn cuts(&self) -> impl Iterator<Item = MinCutResult<W>> + '_ {
+        let tree_cuts = self.tree_edges.iter().map(|&e| vec![e]);
+        let cycle_cuts = self.cycles.iter().flat_map(|cycle| {
+            (0..cycle.len())
+                .flat_map(move |i| (i + 1..cycle.len()).map(move |j| vec![cycle[i], cycle[j]]))
+        });
+        tree_cuts
+            .chain(cycle_cuts)
+            .map(move |removed| self.cut(&removed))
+    }
+
+    fn cut(&self, removed: &[usize]) -> MinCutResult<W> {
+        let mut reached = vec![false; self.num_nodes()];
+        let mut stack = vec![self.node_of[0]];
+        reached[self.node_of[0]] = true;
+        while let Some(x) = stack.pop() {
+            for &(y, e) in self.adj[x].iter() {
+                if !reached[y] && !removed.contains(&e) {
+                    reached[y] = true;
+                    stack.push(y);
+                }
+            }
+        }
+        let sides = self
+            .node_of
+            .iter()
+            .map(|&x| !reached[x] as usize)
+            .collect::<Box<[usize]>>();
+        MinCutResult::from_sides(self.graph, &sides)
+    }
+
+    pub fn to_dot(&self) -> String {
+        let mut vertices = vec![Vec::new(); self.num_nodes()];
+        for (v, &x) in self.node_of.iter().enumerate() {
+            vertices[x].push(v.to_string());
+        }
+
+        let mut dot = String::from("graph cactus {\n");
+        for (x, vertices) in vertices.iter().enumerate() {
+            writeln!(dot, "    {x} [label=\"{}\"];", vertices.join(", ")).unwrap();
+        }
+        for &e in self.tree_edges.iter() {
+            let (x, y) = self.edges[e];
+            writeln!(dot, "    {x} -- {y} [label=\"{}\"];", self.value).unwrap();
+        }
+        for &e in self.cycles.iter().flat_map(|cycle| cycle.iter()) {
+            let (x, y) = self.edges[e];
+            let weight = self.value.to_f64() / 2f64;
+            writeln!(dot, "    {x} -- {y} [label=\"{weight}\"];").unwrap();
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+fn members(set: &[bool]) -> impl Iterator<Item = usize> + '_ {
+    set.iter().enumerate().filter(|(_, &b)| b).map(|(a, _)| a)
+}
+
+// Whether each cut crosses another one, without comparing every pair. Going from the largest cut
+// down, each atom remembers the last cut holding it. Of the cuts a cut meets that way, the earliest
+// crosses it unless it contains it, and the others cross it as they are no smaller. In a cactus
+// every crossing cut is caught, either on its own turn or on that of a cut it crosses, and a cut
+// missed leaves the others non-laminar, which the cactus reports.
+fn crossing_sets(sets: &[Vec<bool>]) -> Vec<bool> {
+    let members = sets
+        .iter()
+        .map(|set| members(set).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    let mut order = (0..sets.len()).collect::<Vec<_>>();
+    order.sort_by_key(|&i| std::cmp::Reverse(members[i].len()));
+    let mut position = vec![0; sets.len()];
+    for (p, &i) in order.iter().enumerate() {
+        position[i] = p;
+    }
+
+    let mut crossing = vec![false; sets.len()];
+    let mut owner = vec![None; sets.first().map_or(0, Vec::len)];
+    for &x in order.iter() {
+        let mut owners = members[x].iter().map(|&a| owner[a]).collect::<Vec<_>>();
+        owners.sort_by_key(|y: &Option<usize>| y.map(|y| position[y]));
+        owners.dedup();
+        for (k, y) in owners.into_iter().enumerate() {
+            if let Some(y) = y {
+                if k > 0 || members[x].iter().any(|&a| !sets[y][a]) {
+                    crossing[x] = true;
+                    crossing[y] = true;
+                }
+            }
+        }
+        for &a in members[x].iter() {
+            owner[a] = Some(x);
+        }
+    }
+    crossing
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use crate::cactus::Cactus;
+    use crate::data_structures::api::DirectedEdge;
+    use crate::data_structures::graph::Graph;
+    use crate::karger::KargerAlgo;
+    use crate::karger_stein::KargerSteinAlgo;
+    use crate::min_cut_algo::{MinCutEnumerator, MinCutError};
+    use crate::traits::EdgeWeightedGraph;
+
+    fn undirected_graph(n: usize, edges: &[(usize, usize, usize)]) -> Graph {
+        let edges = edges
+            .iter()
+            .flat_map(|&(v, w, weight)| [(v, w, weight), (w, v, weight)])
+            .map(DirectedEdge::from);
+        Graph::from_directed_edges(n, edges).unwrap()
+    }
+
+    #[test]
+    fn cactus_sanity_test() {
+        let graph = undirected_graph(4, &[(0, 1, 3), (0, 2, 1), (1, 2, 8), (1, 3, 8), (2, 3, 5)]);
+        let cactus = Cactus::with_seed(&graph, 1).unwrap();
+        assert_eq!(cactus.value(), 8);
+        assert_eq!(cactus.num_nodes(), 2);
+        assert!(cactus.separates(0, 3));
+        assert!(!cactus.separates(1, 2));
+        assert_eq!(
+            cactus.to_dot(),
+            "graph cactus {\n    0 [label=\"0\"];\n    1 [label=\"1, 2, 3\"];\n    0 -- 1 [label=\"8\"];\n}\n"
+        );
+    }
+
+    #[test]
+    fn cactus_cycles_test() {
+        // Two 4-cycles sharing vertex 3, with vertex 7 hanging off vertex 0.
+        let edges = [
+            (0, 1, 1),
+            (1, 2, 1),
+            (2, 3, 1),
+            (3, 0, 1),
+            (3, 4, 1),
+            (4, 5, 1),
+            (5, 6, 1),
+            (6, 3, 1),
+            (0, 7, 2),
+        ];
+        let graph = undirected_graph(8, &edges);
+        let cactus = Cactus::with_seed(&graph, 2).unwrap();
+        assert_eq!(cactus.value(), 4);
+        assert_eq!(cactus.num_nodes(), 8);
+        assert_eq!(cactus.num_cuts(), 13);
+        assert!((0..8).all(|v| (v + 1..8).all(|w| cactus.separates(v, w))));
+
+        let min_cuts = KargerSteinAlgo::<3>::with_seed(&graph, 3)
+            .enumerate_min_cuts(0.99, false)
+            .unwrap();
+        let expected = min_cuts
+            .cuts()
+            .iter()
+            .map(|cut| cut.partition().0.to_vec())
+            .collect::<BTreeSet<_>>();
+        let actual = cactus
+            .cuts()
+            .map(|cut| cut.partition().0.to_vec())
+            .collect::<BTreeSet<_>>();
+        assert_eq!(expected.len(), 13);
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn cactus_errors_test() {
+        let single = undirected_graph(1, &[]);
+        assert_eq!(
+            Cactus::new(&single).err(),
+            Some(MinCutError::TooFewVertices { num_vertices: 1 })
+        );
+
+        let disconnected = undirected_graph(4, &[(0, 1, 2), (2, 3, 1)]);
+        assert_eq!(
+            Cactus::with_seed(&disconnected, 1).err(),
+            Some(MinCutError::ZeroMinCut)
+        );
+        let edgeless = undirected_graph(3, &[]);
+        assert_eq!(Cactus::new(&edgeless).err(), Some(MinCutError::ZeroMinCut));
+
+        // Two crossing min cuts of a cycle, without the min cuts they split each other into.
+        let cycle = undirected_graph(6, &(0..6).map(|v| (v, (v + 1) % 6, 1)).collect::<Vec<_>>());
+        let min_cuts = KargerAlgo::with_seed(&cycle, 10)
+            .enumerate_min_cuts_n(2, false)
+            .unwrap();
+        assert_eq!(min_cuts.cuts().len(), 2);
+        assert_eq!(
+            Cactus::from_min_cuts(&cycle, &min_cuts).err(),
+            Some(MinCutError::IncompleteMinCuts)
+        );
+        assert_eq!(Cactus::with_seed(&cycle, 1).unwrap().num_cuts(), 15);
+    }
+}
@@ -1,16 +1,123 @@
-pub enum DirectedEdge {
+use std::fmt::{Display, Formatter};
+
+use crate::weight::WeightOverflow;
+
+/// An arc from the first vertex to the second, of weight 1 unless weighted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DirectedEdge<W = usize> {
     Unweighted(usize, usize),
-    Weighted(usize, usize, usize),
+    Weighted(usize, usize, W),
 }
 
-impl From<(usize, usize)> for DirectedEdge {
+impl<W> From<(usize, usize)> for DirectedEdge<W> {
     fn from(value: (usize, usize)) -> Self {
         DirectedEdge::Unweighted(value.0, value.1)
     }
 }
 
-impl From<(usize, usize, usize)> for DirectedEdge {
-    fn from(value: (usize, usize, usize)) -> Self {
+impl<W> From<(usize, usize, W)> for DirectedEdge<W> {
+    fn from(value: (usize, usize, W)) -> Self {
         DirectedEdge::Weighted(value.0, value.1, value.2)
     }
 }
+
+/// Why a list of arcs does not make a graph.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphError {
+    /// An arc has an endpoint that is not one of the vertices `0..num_vertices`.
+    VertexOutOfRange {
+        vertex: usize,
+        num_vertices: usize,
+    },
+    WeightOverflow(WeightOverflow),
+}
+
+impl From<WeightOverflow> for GraphError {
+    fn from(err: WeightOverflow) -> Self {
+        GraphError::WeightOverflow(err)
+    }
+}
+
+impl Display for GraphError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GraphError::VertexOutOfRange {
+                vertex,
+                num_vertices,
+            } => write!(
+                f,
+                "vertex {vertex} is out of range for a graph of {num_vertices} vertices"
+            ),
+            GraphError::WeightOverflow(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl std::error::Error for GraphError {}
+
+/// How to make a graph undirected, that is give the arcs from `v` to `w` and back the same weight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Symmetrize {
+    /// Both arcs weigh the sum of their weights.
+    Sum,
+    /// Both arcs weigh the larger of their weights.
+    Max,
+    /// Both arcs must already weigh the same.
+    RequireEqual,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymmetryViolation<W = usize> {
+    /// An arc from `from` to `to` without one back.
+    MissingReverse { from: usize, to: usize, weight: W },
+    /// Arcs both ways between `v < w` that weigh differently.
+    WeightMismatch {
+        v: usize,
+        w: usize,
+        forward: W,
+        backward: W,
+    },
+}
+
+impl<W: Display> Display for SymmetryViolation<W> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SymmetryViolation::MissingReverse { from, to, weight } => {
+                write!(
+                    f,
+                    "arc {from} -> {to} of weight {weight} has no reverse arc"
+                )
+            }
+            SymmetryViolation::WeightMismatch {
+                v,
+                w,
+                forward,
+                backward,
+            } => write!(
+                f,
+                "arc {v} -> {w} weighs {forward} but arc {w} -> {v} weighs {backward}"
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymmetrizeError<W = usize> {
+    Asymmetric(SymmetryViolation<W>),
+    WeightOverflow(WeightOverflow),
+}
+
+impl<W> From<WeightOverflow> for SymmetrizeError<W> {
+    fn from(err: WeightOverflow) -> Self {
+        SymmetrizeError::WeightOverflow(err)
+    }
+}
+
+impl<W: Display> Display for SymmetrizeError<W> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SymmetrizeError::Asymmetric(violation) => write!(f, "asymmetric graph: {violation}"),
+            SymmetrizeError::WeightOverflow(err) => write!(f, "{err}"),
+        }
+    }
+}
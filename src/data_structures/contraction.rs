@@ -0,0 +1,121 @@
+use crate::data_structures::graph::Graph;
+use crate::traits::EdgeWeightedGraph;
+
+/// A contracted graph with the vertex of it every vertex of the original graph became. Contractions
+/// of the contracted graph compose with `then`, so that the map always starts from the original
+/// graph, however many times it was contracted.
+#[derive(Debug, Clone)]
+pub struct Contraction<G = Graph> {
+    graph: G,
+    vertex_map: Box<[usize]>,
+}
+
+impl<G: EdgeWeightedGraph> Contraction<G> {
+    pub(crate) fn new(graph: G, vertex_map: Box<[usize]>) -> Contraction<G> {
+        debug_assert!(vertex_map.iter().all(|&v| v < graph.num_vertices()));
+        Self { graph, vertex_map }
+    }
+
+    /// The graph as its own contraction, every vertex staying where it is.
+    pub fn identity(graph: G) -> Contraction<G> {
+        let vertex_map = (0..graph.num_vertices()).collect();
+        Self::new(graph, vertex_map)
+    }
+
+    /// The contracted graph.
+    pub fn graph(&self) -> &G {
+        &self.graph
+    }
+
+    /// The vertex of the contracted graph each vertex of the original graph became.
+    pub fn vertex_map(&self) -> &[usize] {
+        &self.vertex_map
+    }
+
+    /// The contracted graph, without the vertex map.
+    pub fn into_graph(self) -> G {
+        self.graph
+    }
+
+    /// The contracted graph and the vertex map.
+    pub fn into_parts(self) -> (G, Box<[usize]>) {
+        (self.graph, self.vertex_map)
+    }
+
+    /// The original vertices merged into each vertex of the contracted graph, in increasing order.
+    pub fn members(&self) -> Box<[Box<[usize]>]> {
+        let mut members = vec![Vec::new(); self.graph.num_vertices()];
+        for (v, &x) in self.vertex_map.iter().enumerate() {
+            members[x].push(v);
+        }
+        members.into_iter().map(Vec::into_boxed_slice).collect()
+    }
+
+    /// The original vertices merged into the given vertices of the contracted graph, in increasing
+    /// order, such as a side of a cut of the contracted graph.
+    pub fn lift(&self, vertices: &[usize]) -> Box<[usize]> {
+        let mut chosen = vec![false; self.graph.num_vertices()];
+        for &x in vertices {
+            chosen[x] = true;
+        }
+        (0..self.vertex_map.len())
+            .filter(|&v| chosen[self.vertex_map[v]])
+            .collect()
+    }
+
+    /// Follows this contraction by `next`, a contraction of the contracted graph.
+    pub fn then(self, next: Contraction<G>) -> Contraction<G> {
+        assert_eq!(
+            next.vertex_map.len(),
+            self.graph.num_vertices(),
+            "the next contraction is not one of the contracted graph"
+        );
+        let vertex_map = self
+            .vertex_map
+            .iter()
+            .map(|&x| next.vertex_map[x])
+            .collect();
+        Self::new(next.graph, vertex_map)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data_structures::api::DirectedEdge;
+    use crate::data_structures::contraction::Contraction;
+    use crate::data_structures::graph::Graph;
+    use crate::traits::EdgeWeightedGraph;
+
+    #[test]
+    fn compose_contractions() {
+        // A path 0 - 1 - 2 - 3 - 4 - 5.
+        let edges = (0..5).map(|v| DirectedEdge::from((v, v + 1, v + 1)));
+        let graph: Graph = Graph::from_undirected_edges(6, edges).unwrap();
+
+        let contraction = graph.contract_vertices(&[4, 5]);
+        assert_eq!(contraction.vertex_map(), [0, 1, 2, 3, 4, 4]);
+        let next = contraction.graph().contract_edge(0, 1);
+        let contraction = contraction.then(next);
+        assert_eq!(contraction.vertex_map(), [0, 0, 1, 2, 3, 3]);
+        let next = contraction.graph().contract_edge(1, 2);
+        let contraction = contraction.then(next);
+
+        assert_eq!(contraction.vertex_map(), [0, 0, 1, 1, 2, 2]);
+        assert_eq!(
+            contraction.members()[..],
+            [vec![0, 1], vec![2, 3], vec![4, 5]].map(Vec::into_boxed_slice)
+        );
+        assert_eq!(contraction.lift(&[0, 2])[..], [0, 1, 4, 5]);
+        assert_eq!(contraction.graph().edge_weight(0, 1), Some(2));
+        assert_eq!(contraction.graph().edge_weight(1, 2), Some(4));
+        assert_eq!(
+            graph.cut_weight(&contraction.lift(&[0])),
+            contraction.graph().cut_weight(&[0])
+        );
+
+        let identity = Contraction::identity(graph.clone());
+        let (contracted, vertex_map) = identity.then(graph.contract_edge(2, 3)).into_parts();
+        assert_eq!(vertex_map[..], [0, 1, 2, 2, 3, 4]);
+        assert_eq!(contracted.num_vertices(), 5);
+    }
+}
@@ -1,25 +1,28 @@
-pub struct FenwickTree {
+use crate::weight::Weight;
+
+pub struct FenwickTree<W = usize> {
     n: usize,
-    tree: Box<[usize]>,
+    tree: Box<[W]>,
 }
 
-impl FenwickTree {
+impl<W: Weight> FenwickTree<W> {
     #[allow(dead_code)]
     pub fn new(n: usize) -> Self {
         FenwickTree {
             n: n + 1,
-            tree: vec![0; n + 1].into_boxed_slice(),
+            tree: vec![W::ZERO; n + 1].into_boxed_slice(),
         }
     }
 
-    pub fn from(arr: &[usize]) -> Self {
-        let mut tree = vec![0; arr.len() + 1].into_boxed_slice();
+    pub fn from(arr: &[W]) -> Self {
+        let mut tree = vec![W::ZERO; arr.len() + 1].into_boxed_slice();
         for i in 1..=arr.len() {
             tree[i] += arr[i - 1];
             let j = i as isize;
             let j = (j + (j & -j)) as usize;
             if j <= arr.len() {
-                tree[j] += tree[i];
+                let value = tree[i];
+                tree[j] += value;
             }
         }
         FenwickTree {
@@ -28,9 +31,9 @@ impl FenwickTree {
         }
     }
 
-    pub fn query(&self, id: usize) -> usize {
+    pub fn query(&self, id: usize) -> W {
         let mut id = id as isize + 1;
-        let mut sum = 0;
+        let mut sum = W::ZERO;
         while id > 0 {
             sum += self.tree[id as usize];
             id -= id & -id;
@@ -38,7 +41,7 @@ impl FenwickTree {
         sum
     }
 
-    pub fn update(&mut self, id: usize, val: usize, subtract: bool) {
+    pub fn update(&mut self, id: usize, val: W, subtract: bool) {
         let mut id = id as isize + 1;
         while id < self.n as isize {
             if subtract {
@@ -50,9 +53,9 @@ impl FenwickTree {
         }
     }
 
-    pub fn lower_entry(&self, val: usize) -> (usize, usize) {
+    pub fn lower_entry(&self, val: W) -> (usize, W) {
         if val <= self.tree[1] {
-            return (0, 0);
+            return (0, W::ZERO);
         }
 
         let (mut start, mut end) = (1, self.n - 2);
@@ -69,7 +72,7 @@ impl FenwickTree {
     }
 
     #[allow(dead_code)]
-    pub fn sum(&self) -> usize {
+    pub fn sum(&self) -> W {
         self.query(self.n - 2)
     }
 }
@@ -80,12 +83,12 @@ mod tests {
 
     #[test]
     fn test_fenwick_tree() {
-        let fenwick = FenwickTree::new(5);
+        let fenwick = FenwickTree::<usize>::new(5);
         assert_eq!(fenwick.n, 6);
         assert_eq!(fenwick.sum(), 0);
         assert_eq!(fenwick.tree[..], [0, 0, 0, 0, 0, 0]);
 
-        let mut fenwick = FenwickTree::from(&[1, 2, 3, 4, 5, 6, 7, 8, 9, 10]);
+        let mut fenwick = FenwickTree::<usize>::from(&[1, 2, 3, 4, 5, 6, 7, 8, 9, 10]);
         assert_eq!(fenwick.n, 11);
         assert_eq!(fenwick.sum(), 55);
         assert_eq!(fenwick.tree[..], [0, 1, 3, 3, 10, 5, 11, 7, 36, 9, 19]);
@@ -114,8 +117,13 @@ mod tests {
         assert_eq!(fenwick.lower_entry(55), (9, 45));
         assert_eq!(fenwick.lower_entry(100), (9, 45));
 
-        let fenwick = FenwickTree::from(&[5, 5, 5, 0, 0, 0, 0, 5, 5, 5]);
+        let fenwick = FenwickTree::<usize>::from(&[5, 5, 5, 0, 0, 0, 0, 5, 5, 5]);
         assert_eq!(fenwick.lower_entry(15), (2, 10));
         assert_eq!(fenwick.lower_entry(18), (7, 15));
+
+        let fenwick = FenwickTree::<f64>::from(&[0.5, 0.25, 0.0, 1.25]);
+        assert_eq!(fenwick.sum(), 2.0);
+        assert_eq!(fenwick.lower_entry(0.6), (1, 0.5));
+        assert_eq!(fenwick.lower_entry(0.8), (3, 0.75));
     }
 }
@@ -1,31 +1,38 @@
-use std::borrow::Borrow;
-use std::collections::HashMap;
+use std::borrow::{Borrow, Cow};
+use std::collections::BTreeMap;
 use std::slice::{Iter, IterMut};
 
-use rand::rngs::ThreadRng;
+use rand::seq::index::sample;
 use rand::Rng;
 
-use crate::data_structures::api::DirectedEdge;
+use crate::data_structures::api::{
+    DirectedEdge, GraphError, Symmetrize, SymmetrizeError, SymmetryViolation,
+};
 use crate::traits::{EdgeWeightedGraph, MinCutGraph};
+use crate::weight::{Weight, WeightOverflow};
 
+use super::contraction::Contraction;
 use super::fenwick_tree::FenwickTree;
+use super::graph_builder::check_edge;
 use super::graph_util::*;
 use super::union_find::UnionFind;
 
-#[derive(Debug)]
-pub struct Graph {
+/// A weighted directed graph on the vertices `0..n`, as adjacency lists of arcs. Parallel arcs are
+/// merged into one and loops dropped, and twice its total weight fits `W`.
+#[derive(Debug, Clone)]
+pub struct Graph<W = usize> {
     n: usize,
-    weight: usize,
-    adj: Box<[Node]>,
+    weight: W,
+    adj: Vec<Node<W>>,
 }
 
-impl Graph {
+impl<W: Weight> Graph<W> {
     fn from<T, U>(adj: &T) -> Self
     where
-        U: Borrow<[(usize, usize)]>,
+        U: Borrow<[(usize, W)]>,
         T: Borrow<[U]>,
     {
-        let adj: Box<[Node]> = adj
+        let adj: Vec<Node<W>> = adj
             .borrow()
             .iter()
             .enumerate()
@@ -39,41 +46,479 @@ impl Graph {
         }
     }
 
-    fn pick_random_edge<'a>(
-        rng: &mut ThreadRng,
-        weight: usize,
-        adj: &'a Box<[Option<Node>]>,
+    // Adds up the weights of parallel arcs and drops loops, without checking for overflow. The
+    // endpoints must be vertices.
+    fn from_arcs(n: usize, arcs: impl Iterator<Item = (usize, usize, W)>) -> Self {
+        let mut adj = vec![BTreeMap::<usize, W>::new(); n];
+        for (v, w, weight) in arcs {
+            debug_assert!(v < n && w < n);
+            if v != w {
+                *adj[v].entry(w).or_insert(W::ZERO) += weight;
+            }
+        }
+        let adj = adj
+            .into_iter()
+            .map(|it| it.into_iter().collect::<Box<[(usize, W)]>>())
+            .collect::<Box<[Box<[(usize, W)]>]>>();
+        Graph::from(&adj)
+    }
+
+    // `from_directed_edges` for arcs whose endpoints are known to be vertices.
+    pub(crate) fn from_checked_edges(
+        n: usize,
+        directed_edges: impl Iterator<Item = DirectedEdge<W>>,
+    ) -> Result<Self, WeightOverflow> {
+        let arcs = directed_edges
+            .map(|edge| match edge {
+                DirectedEdge::Unweighted(v, w) => (v, w, W::ONE),
+                DirectedEdge::Weighted(v, w, weight) => (v, w, weight),
+            })
+            .collect::<Vec<_>>();
+        let total = arcs
+            .iter()
+            .filter(|&&(v, w, _)| v != w)
+            .try_fold(W::ZERO, |total, &(_, _, weight)| total.checked_add(weight));
+        match total.and_then(|total| total.checked_add(total)) {
+            Some(_) => Ok(Graph::from_arcs(n, arcs.into_iter())),
+            None => Err(WeightOverflow),
+        }
+    }
+
+    // Fits the weight type, as the total weight of the graph, counted twice, does.
+    pub(crate) fn symmetrized(&self) -> Graph<W> {
+        let arcs = self.adj.iter().flat_map(|node| {
+            node.edges.iter().flat_map(|edge| {
+                [
+                    (node.vertex, edge.other, edge.weight),
+                    (edge.other, node.vertex, edge.weight),
+                ]
+            })
+        });
+        Graph::from_arcs(self.n, arcs)
+    }
+
+    /// The arcs without a reverse arc of the same weight, each pair of vertices reported once.
+    pub fn symmetry_violations(&self) -> Vec<SymmetryViolation<W>> {
+        let arcs = self.arc_weights();
+        arcs.iter()
+            .filter_map(|(&(v, w), &forward)| match arcs.get(&(w, v)) {
+                None => Some(SymmetryViolation::MissingReverse {
+                    from: v,
+                    to: w,
+                    weight: forward,
+                }),
+                Some(&backward) if v < w && backward != forward => {
+                    Some(SymmetryViolation::WeightMismatch {
+                        v,
+                        w,
+                        forward,
+                        backward,
+                    })
+                }
+                Some(_) => None,
+            })
+            .collect()
+    }
+
+    /// Summing the arcs both ways doubles the total weight, which may overflow.
+    pub fn symmetrize(&self, policy: Symmetrize) -> Result<Graph<W>, SymmetrizeError<W>> {
+        match policy {
+            Symmetrize::Sum => {
+                let directed_edges = self.adj.iter().flat_map(|node| {
+                    node.edges.iter().flat_map(|edge| {
+                        [
+                            DirectedEdge::Weighted(node.vertex, edge.other, edge.weight),
+                            DirectedEdge::Weighted(edge.other, node.vertex, edge.weight),
+                        ]
+                    })
+                });
+                Ok(Graph::from_checked_edges(self.n, directed_edges)?)
+            }
+            Symmetrize::Max => {
+                let mut edges = BTreeMap::new();
+                for ((v, w), weight) in self.arc_weights() {
+                    let max_weight = edges.entry((v.min(w), v.max(w))).or_insert(W::ZERO);
+                    if weight > *max_weight {
+                        *max_weight = weight;
+                    }
+                }
+                let directed_edges = edges.into_iter().flat_map(|((v, w), weight)| {
+                    [
+                        DirectedEdge::Weighted(v, w, weight),
+                        DirectedEdge::Weighted(w, v, weight),
+                    ]
+                });
+                Ok(Graph::from_checked_edges(self.n, directed_edges)?)
+            }
+            Symmetrize::RequireEqual => match self.symmetry_violations().first() {
+                Some(&violation) => Err(SymmetrizeError::Asymmetric(violation)),
+                None => Ok(self.clone()),
+            },
+        }
+    }
+
+    fn arc_weights(&self) -> BTreeMap<(usize, usize), W> {
+        self.adj
+            .iter()
+            .flat_map(|node| {
+                node.edges
+                    .iter()
+                    .map(|edge| ((node.vertex, edge.other), edge.weight))
+            })
+            .collect()
+    }
+
+    /// Every arc as `(v, w, weight)`, by source vertex.
+    pub fn edges(&self) -> impl Iterator<Item = (usize, usize, W)> + '_ {
+        self.adj.iter().flat_map(|node| {
+            node.edges
+                .iter()
+                .map(|edge| (node.vertex, edge.other, edge.weight))
+        })
+    }
+
+    /// The heads of the arcs leaving `v`, with their weights.
+    pub fn neighbors(&self, v: usize) -> impl Iterator<Item = (usize, W)> + '_ {
+        self.adj[v]
+            .edges
+            .iter()
+            .map(|edge| (edge.other, edge.weight))
+    }
+
+    /// The total weight of the arcs leaving `v`.
+    pub fn weighted_degree(&self, v: usize) -> W {
+        self.adj[v].weight
+    }
+
+    /// The weight of the arc from `v` to `w`, if there is one.
+    pub fn edge_weight(&self, v: usize, w: usize) -> Option<W> {
+        self.neighbors(v)
+            .find(|&(other, _)| other == w)
+            .map(|(_, weight)| weight)
+    }
+
+    /// A vertex of least weighted degree, the smallest one on ties. Alone on its side, it gives a
+    /// cut of that weight in a symmetric graph.
+    pub fn min_degree_vertex(&self) -> Option<usize> {
+        self.adj
+            .iter()
+            .min_by(|a, b| a.weight.total_cmp(&b.weight))
+            .map(|node| node.vertex)
+    }
+
+    /// The weight of the arcs between the vertices of `side` and the others, both ways.
+    pub fn cut_weight(&self, side: &[usize]) -> W {
+        let mut in_side = vec![false; self.n];
+        for &v in side {
+            in_side[v] = true;
+        }
+        self.edges()
+            .filter(|&(v, w, _)| in_side[v] != in_side[w])
+            .map(|(_, _, weight)| weight)
+            .sum()
+    }
+
+    /// Adds an undirected edge between `v` and `w`, that is an arc each way, on top of any arcs
+    /// already between them. Loops are dropped, as when building the graph. Panics like
+    /// `GraphBuilder::add_edge`. `add_arc` adds the arc from `v` alone.
+    pub fn add_edge(&mut self, v: usize, w: usize, weight: W) -> Result<(), WeightOverflow> {
+        check_edge(self.n, v, w, weight);
+        if v == w {
+            return Ok(());
+        }
+        Self::check_total(self.weight, &[weight, weight])?;
+        let forward = self.edge_weight(v, w).unwrap_or(W::ZERO) + weight;
+        let backward = self.edge_weight(w, v).unwrap_or(W::ZERO) + weight;
+        self.replace_arc(v, w, Some(forward));
+        self.replace_arc(w, v, Some(backward));
+        Ok(())
+    }
+
+    /// Removes the undirected edge between `v` and `w`, that is the arcs both ways, and returns
+    /// the weight of the one from `v`. `remove_arc` keeps the arc from `w`.
+    pub fn remove_edge(&mut self, v: usize, w: usize) -> Option<W> {
+        let forward = self.replace_arc(v, w, None);
+        self.replace_arc(w, v, None);
+        forward
+    }
+
+    /// Gives the undirected edge between `v` and `w`, that is the arcs both ways, the weight,
+    /// adding them if missing, and returns the previous weight of the one from `v`.
+    /// `set_arc_weight` leaves the arc from `w` as it is.
+    pub fn set_weight(
+        &mut self,
+        v: usize,
+        w: usize,
+        weight: W,
+    ) -> Result<Option<W>, WeightOverflow> {
+        check_edge(self.n, v, w, weight);
+        if v == w {
+            return Ok(None);
+        }
+        let forward = self.edge_weight(v, w);
+        let backward = self.edge_weight(w, v);
+        let rest = self.weight - forward.unwrap_or(W::ZERO) - backward.unwrap_or(W::ZERO);
+        Self::check_total(rest, &[weight, weight])?;
+        self.replace_arc(v, w, Some(weight));
+        self.replace_arc(w, v, Some(weight));
+        Ok(forward)
+    }
+
+    /// Adds an arc from `v` to `w` on top of any arc already there, leaving the graph asymmetric
+    /// unless the reverse arc follows. Loops are dropped. Panics like `GraphBuilder::add_edge`.
+    pub fn add_arc(&mut self, v: usize, w: usize, weight: W) -> Result<(), WeightOverflow> {
+        check_edge(self.n, v, w, weight);
+        if v == w {
+            return Ok(());
+        }
+        Self::check_total(self.weight, &[weight])?;
+        let forward = self.edge_weight(v, w).unwrap_or(W::ZERO) + weight;
+        self.replace_arc(v, w, Some(forward));
+        Ok(())
+    }
+
+    /// Removes the arc from `v` to `w` alone, and returns its weight.
+    pub fn remove_arc(&mut self, v: usize, w: usize) -> Option<W> {
+        self.replace_arc(v, w, None)
+    }
+
+    /// Gives the arc from `v` to `w` alone the weight, adding it if missing, and returns its
+    /// previous weight.
+    pub fn set_arc_weight(
+        &mut self,
+        v: usize,
+        w: usize,
+        weight: W,
+    ) -> Result<Option<W>, WeightOverflow> {
+        check_edge(self.n, v, w, weight);
+        if v == w {
+            return Ok(None);
+        }
+        let forward = self.edge_weight(v, w);
+        Self::check_total(self.weight - forward.unwrap_or(W::ZERO), &[weight])?;
+        self.replace_arc(v, w, Some(weight));
+        Ok(forward)
+    }
+
+    /// Adds a vertex without edges, and returns it.
+    pub fn add_vertex(&mut self) -> usize {
+        self.adj.push(Node::from(self.n, &[]));
+        self.n += 1;
+        self.n - 1
+    }
+
+    /// Removes `v` with its arcs. The vertices after `v` move down by one, so that the vertices
+    /// stay numbered from 0.
+    pub fn remove_vertex(&mut self, v: usize) {
+        assert!(
+            v < self.n,
+            "vertex {v} is out of range for a graph of {} vertices",
+            self.n
+        );
+        let compact = |u: usize| if u > v { u - 1 } else { u };
+        let mut removed = self.adj.remove(v).weight;
+        for node in self.adj.iter_mut() {
+            if let Some(i) = node.edges.iter().position(|edge| edge.other == v) {
+                let weight = node.edges.remove(i).weight;
+                node.weight = Self::adjusted(node.weight, weight, W::ZERO, &node.edges);
+                removed += weight;
+            }
+            node.vertex = compact(node.vertex);
+            for edge in node.edges.iter_mut() {
+                edge.other = compact(edge.other);
+            }
+        }
+        self.n -= 1;
+        self.weight = self.moved_total(removed, W::ZERO);
+    }
+
+    // Twice the total weight must still fit, as when building the graph, once arcs of the given
+    // weights join the arcs weighing `rest`.
+    fn check_total(rest: W, arcs: &[W]) -> Result<(), WeightOverflow> {
+        let total = arcs
+            .iter()
+            .try_fold(rest, |total, &weight| total.checked_add(weight));
+        match total.and_then(|total| total.checked_add(total)) {
+            Some(_) => Ok(()),
+            None => Err(WeightOverflow),
+        }
+    }
+
+    // Sets or removes the arc from `v` to `w` in place, and returns its previous weight.
+    fn replace_arc(&mut self, v: usize, w: usize, weight: Option<W>) -> Option<W> {
+        let node = &mut self.adj[v];
+        let position = node.edges.iter().position(|edge| edge.other == w);
+        let previous = position.map(|i| node.edges[i].weight);
+        match (position, weight) {
+            (Some(i), Some(weight)) => node.edges[i].weight = weight,
+            (Some(i), None) => {
+                node.edges.remove(i);
+            }
+            (None, Some(weight)) => node.edges.push(Edge { other: w, weight }),
+            (None, None) => return None,
+        }
+
+        let (old, new) = (previous.unwrap_or(W::ZERO), weight.unwrap_or(W::ZERO));
+        node.weight = Self::adjusted(node.weight, old, new, &node.edges);
+        self.weight = self.moved_total(old, new);
+        previous
+    }
+
+    // The total weight once arcs weighing `old` in all weigh `new`, with the vertex weights already
+    // updated. Floating-point totals move by the difference too, whose rounding the contraction
+    // tolerates, and are summed again only once they reach 0 while arcs may be left.
+    fn moved_total(&self, old: W, new: W) -> W {
+        let total = self.weight - old + new;
+        match W::EXACT || total > W::ZERO {
+            true => total,
+            false => self.adj.iter().map(|node| node.weight).sum(),
+        }
+    }
+
+    // The weight of a vertex once one of its arcs goes from `old` to `new`: moved by the difference
+    // for integer weights, and summed again from its arcs for floating-point ones, whose
+    // differences round.
+    fn adjusted(weight: W, old: W, new: W, edges: &[Edge<W>]) -> W {
+        match W::EXACT {
+            true => weight - old + new,
+            false => edges.iter().map(|edge| edge.weight).sum(),
+        }
+    }
+
+    pub(crate) fn adjacency_list(&self) -> &[Node<W>] {
+        self.adj.borrow()
+    }
+
+    /// The connected components of the graph taken as undirected, as the component of every vertex.
+    /// Components are numbered in the order of their smallest vertex.
+    pub fn connected_components(&self) -> Box<[usize]> {
+        let mut union_find = UnionFind::new(self.n);
+        for node in self.adj.iter() {
+            for edge in node.edges.iter() {
+                union_find.union(node.vertex, edge.other);
+            }
+        }
+        let mut component_of_root = vec![usize::MAX; self.n];
+        let mut num_components = 0;
+        (0..self.n)
+            .map(|v| {
+                let root = union_find.root(v);
+                if component_of_root[root] == usize::MAX {
+                    component_of_root[root] = num_components;
+                    num_components += 1;
+                }
+                component_of_root[root]
+            })
+            .collect()
+    }
+
+    pub(crate) fn reversed(&self) -> Graph<W> {
+        let arcs = self.adj.iter().flat_map(|node| {
+            node.edges
+                .iter()
+                .map(|edge| (edge.other, node.vertex, edge.weight))
+        });
+        Graph::from_arcs(self.n, arcs)
+    }
+
+    /// Merges `v` and `w` into one vertex, dropping the arcs between them. See `contract_vertices`.
+    pub fn contract_edge(&self, v: usize, w: usize) -> Contraction<Graph<W>> {
+        self.contract_vertices(&[v, w])
+    }
+
+    /// Merges the vertices into one, which takes the place of the smallest of them, the later
+    /// vertices moving down to keep the numbering dense. Arcs inside the merged vertex are dropped
+    /// and parallel arcs add up.
+    pub fn contract_vertices(&self, vertices: &[usize]) -> Contraction<Graph<W>> {
+        for &v in vertices {
+            assert!(
+                v < self.n,
+                "vertex {v} is out of range for a graph of {} vertices",
+                self.n
+            );
+        }
+        let mut vertex_map = UnionFind::new(self.n);
+        // Merged into the smallest vertex first, which then stays the root of the larger set.
+        if let Some(&first) = vertices.iter().min() {
+            for &v in vertices {
+                vertex_map.union(first, v);
+            }
+        }
+        self.condense(vertex_map)
+    }
+
+    pub(crate) fn condense(&self, vertex_map: UnionFind) -> Contraction<Graph<W>> {
+        let n = vertex_map.count;
+        let vertex_map = vertex_map.condense(&mut MergeUtil::new(self.n));
+        let mut merge_util = MergeUtil::new(self.n);
+
+        let mut members = vec![Vec::new(); n];
+        for node in self.adj.iter() {
+            members[vertex_map[node.vertex]].push(node.edges.iter());
+        }
+
+        let adj = members
+            .iter_mut()
+            .enumerate()
+            .map(|(x, edges)| {
+                Self::vertex_condenser(x, edges.iter_mut(), &mut merge_util, |v| vertex_map[v])
+            })
+            .collect::<Vec<Node<W>>>();
+
+        let graph = Graph {
+            n,
+            weight: adj.iter().map(|node| node.weight).sum(),
+            adj,
+        };
+        Contraction::new(graph, vertex_map)
+    }
+
+    // Rounding errors of floating-point weights may make the sample miss every edge, in which case
+    // no edge is picked.
+    fn pick_random_edge<'a, R: Rng + ?Sized>(
+        rng: &mut R,
+        weight: W,
+        adj: &'a [Option<Cow<'_, Node<W>>>],
         vertex_map: &mut UnionFind,
-        vertex_weights_map: &FenwickTree,
-    ) -> (usize, &'a Node, usize, &'a Node) {
-        let r = rng.gen_range(0..weight) + 1;
+        vertex_weights_map: &FenwickTree<W>,
+    ) -> Option<(usize, &'a Node<W>, usize, &'a Node<W>)> {
+        let r = W::sample(rng, weight);
 
         let (v, mut rw) = vertex_weights_map.lower_entry(r);
-        let n1 = adj[v].as_ref().unwrap();
+        let n1 = adj[v].as_deref()?;
 
         let random_edge = n1
             .edges
             .iter()
-            .skip_while(|edge| {
+            .find(|edge| {
                 rw += edge.weight;
-                rw < r
+                rw >= r
             })
-            .nth(0)
-            .unwrap();
+            .or(n1.edges.last())?;
 
         let w = vertex_map.root(random_edge.other);
-        let n2 = adj[w].as_ref().unwrap();
+        let n2 = adj[w].as_deref().unwrap();
+
+        Some((v, n1, w, n2))
+    }
 
-        (v, n1, w, n2)
+    fn pick_random_pair<'a, R: Rng + ?Sized>(
+        rng: &mut R,
+        adj: &'a [Option<Cow<'_, Node<W>>>],
+    ) -> (usize, &'a Node<W>, usize, &'a Node<W>) {
+        let alive = adj.iter().flatten().map(Cow::as_ref).collect::<Vec<_>>();
+        let pair = sample(rng, alive.len(), 2);
+        let (n1, n2) = (alive[pair.index(0)], alive[pair.index(1)]);
+        (n1.vertex, n1, n2.vertex, n2)
     }
 
     fn vertex_condenser<T: FnMut(usize) -> usize>(
         v: usize,
-        edges: IterMut<Iter<Edge>>,
-        merge_util: &mut MergeUtil,
+        edges: IterMut<Iter<Edge<W>>>,
+        merge_util: &mut MergeUtil<W>,
         mut vertex_mapper: T,
-    ) -> Node {
-        let mut node_weight = 0;
+    ) -> Node<W> {
+        let mut node_weight = W::ZERO;
         let mut stack_size = 0;
 
         let MergeUtil { stack, merge_proxy } = merge_util;
@@ -83,7 +528,7 @@ impl Graph {
             if root == v {
                 continue;
             }
-            if merge_proxy[root] == 0 {
+            if merge_proxy[root] == W::ZERO {
                 stack[stack_size] = root;
                 stack_size += 1;
             }
@@ -95,7 +540,7 @@ impl Graph {
                 let vertex = stack[i];
                 let weight = merge_proxy[vertex];
                 node_weight += weight;
-                merge_proxy[vertex] = 0;
+                merge_proxy[vertex] = W::ZERO;
                 Edge::from(&(vertex, weight))
             })
             .collect();
@@ -108,38 +553,24 @@ impl Graph {
     }
 }
 
-impl EdgeWeightedGraph for Graph {
-    fn from_directed_edges(n: usize, directed_edges: impl Iterator<Item = DirectedEdge>) -> Self {
-        let mut adj = vec![HashMap::<usize, usize>::new(); n];
-        for edge in directed_edges {
-            match edge {
-                DirectedEdge::Unweighted(v, w) => {
-                    assert!(v < n && w < n);
-                    if v == w {
-                        continue;
-                    }
-                    let edge_weight = *adj[v].get(&w).unwrap_or(&0);
-                    adj[v].insert(w, edge_weight + 1);
-                }
-                DirectedEdge::Weighted(v, w, weight) => {
-                    assert!(v < n && w < n);
-                    if v == w {
-                        continue;
-                    }
-                    let edge_weight = *adj[v].get(&w).unwrap_or(&0);
-                    adj[v].insert(w, edge_weight + weight);
-                }
+impl<W: Weight> EdgeWeightedGraph for Graph<W> {
+    type Weight = W;
+
+    fn from_directed_edges(
+        n: usize,
+        directed_edges: impl Iterator<Item = DirectedEdge<W>>,
+    ) -> Result<Self, GraphError> {
+        let directed_edges = directed_edges.collect::<Vec<_>>();
+        for edge in &directed_edges {
+            let (DirectedEdge::Unweighted(v, w) | DirectedEdge::Weighted(v, w, _)) = *edge;
+            if let Some(vertex) = [v, w].into_iter().find(|&vertex| vertex >= n) {
+                return Err(GraphError::VertexOutOfRange {
+                    vertex,
+                    num_vertices: n,
+                });
             }
         }
-        let adj = adj
-            .into_iter()
-            .map(|it| {
-                it.iter()
-                    .map(|(w, weight)| (*w, *weight))
-                    .collect::<Box<[(usize, usize)]>>()
-            })
-            .collect::<Box<[Box<[(usize, usize)]>]>>();
-        Graph::from(&adj)
+        Ok(Graph::from_checked_edges(n, directed_edges.into_iter())?)
     }
 
     fn num_vertices(&self) -> usize {
@@ -150,29 +581,34 @@ impl EdgeWeightedGraph for Graph {
         self.adj.iter().map(|node| node.edges.len()).sum()
     }
 
-    fn weight(&self) -> usize {
+    fn weight(&self) -> W {
         self.adj.iter().map(|node| node.weight).sum()
     }
-
-    fn adjacency_list(&self) -> &[Node] {
-        self.adj.borrow()
-    }
 }
 
-impl MinCutGraph for Graph {
-    fn contract_full(&self) -> Self {
-        self.contract(2)
+impl<W: Weight> MinCutGraph for Graph<W> {
+    fn contract_full<R: Rng + ?Sized>(&self, rng: &mut R) -> Self {
+        self.contract(2, rng)
     }
 
-    fn contract(&self, t: usize) -> Self {
-        let mut rng = rand::thread_rng();
+    fn contract<R: Rng + ?Sized>(&self, t: usize, rng: &mut R) -> Self {
+        self.contract_mapped(t, rng).into_graph()
+    }
 
+    fn contract_mapped<R: Rng + ?Sized>(&self, t: usize, rng: &mut R) -> Contraction<Graph<W>> {
+        assert!(
+            2 <= t && t <= self.n,
+            "cannot contract a graph of {} vertices to {t}, 2 <= t <= {} is needed",
+            self.n,
+            self.n
+        );
         let mut weight = self.weight;
+        // The nodes are borrowed from the graph until merged.
         let mut adj = self
             .adj
             .iter()
-            .map(|node| Some(node.clone()))
-            .collect::<Box<[Option<Node>]>>();
+            .map(|node| Some(Cow::Borrowed(node)))
+            .collect::<Box<[Option<Cow<Node<W>>>]>>();
 
         let mut merge_util = MergeUtil::new(self.n);
 
@@ -181,18 +617,39 @@ impl MinCutGraph for Graph {
             self.adj
                 .iter()
                 .map(|node| node.weight)
-                .collect::<Box<[usize]>>()
+                .collect::<Box<[W]>>()
                 .as_ref(),
         );
 
         for _ in t..self.n {
-            let (v, n1, w, n2) = Self::pick_random_edge(
-                &mut rng,
-                weight,
-                &adj,
-                &mut vertex_map,
-                &vertex_weights_map,
-            );
+            let (v, n1, w, n2) = loop {
+                if weight > W::ZERO {
+                    let edge = Self::pick_random_edge(
+                        rng,
+                        weight,
+                        &adj,
+                        &mut vertex_map,
+                        &vertex_weights_map,
+                    );
+                    if let Some(edge) = edge {
+                        break edge;
+                    }
+                    // Start again from exact sums, which integer weights never need.
+                    weight = adj.iter().flatten().map(|node| node.weight).sum();
+                    vertex_weights_map = FenwickTree::from(
+                        adj.iter()
+                            .map(|node| node.as_ref().map_or(W::ZERO, |node| node.weight))
+                            .collect::<Box<[W]>>()
+                            .as_ref(),
+                    );
+                    if weight > W::ZERO {
+                        continue;
+                    }
+                }
+                // Only a disconnected graph runs out of edges: merging any two super-vertices
+                // then leaves every cut of the contracted graph as heavy as before.
+                break Self::pick_random_pair(rng, &adj);
+            };
 
             let x = vertex_map.union(v, w);
 
@@ -210,14 +667,14 @@ impl MinCutGraph for Graph {
 
             adj[v] = None;
             adj[w] = None;
-            adj[x] = Some(node);
+            adj[x] = Some(Cow::Owned(node));
         }
 
-        let vertex_map = vertex_map.condense(&mut merge_util);
+        let vertex_map = vertex_map.condense(&mut MergeUtil::new(self.n));
 
         let adj = adj
             .iter()
-            .filter_map(|node| node.as_ref())
+            .filter_map(|node| node.as_deref())
             .map(|node| {
                 let x = vertex_map[node.vertex];
                 let vertex_mapper = |v| vertex_map[v];
@@ -230,12 +687,15 @@ impl MinCutGraph for Graph {
             })
             .collect();
 
-        Graph { n: t, weight, adj }
+        Contraction::new(Graph { n: t, weight, adj }, vertex_map)
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
     use crate::data_structures::api::DirectedEdge;
     use crate::data_structures::graph::Graph;
     use crate::data_structures::graph_util::{Edge, MergeUtil, Node};
@@ -271,11 +731,80 @@ mod tests {
         ][..]
             .iter()
             .map(|it| DirectedEdge::from(*it));
-        let actual: Graph = Graph::from_directed_edges(3, edges);
+        let actual: Graph = Graph::from_directed_edges(3, edges).unwrap();
 
         assert_eq!(actual.num_vertices(), 3);
         assert_eq!(actual.num_edges(), 5);
         assert_eq!(actual.weight(), 14);
+
+        let edges = [(0, 1), (1, 2), (2, 1)][..]
+            .iter()
+            .map(|it| DirectedEdge::from(*it));
+        let actual: Graph = Graph::from_undirected_edges(3, edges).unwrap();
+
+        assert_eq!(actual.num_edges(), 4);
+        assert_eq!(actual.weight(), 6);
+        assert_eq!(actual.adjacency_list()[1].edges[1].weight, 2);
+        assert!(actual.symmetry_violations().is_empty());
+    }
+
+    #[test]
+    fn weight_overflow() {
+        use crate::data_structures::api::{GraphError, Symmetrize, SymmetrizeError};
+        use crate::weight::WeightOverflow;
+
+        let heavy = u32::MAX / 4;
+        let arcs = |weights: &[u32]| {
+            weights
+                .iter()
+                .map(|&weight| DirectedEdge::Weighted(0, 1, weight))
+                .collect::<Vec<_>>()
+        };
+
+        let graph = Graph::<u32>::from_directed_edges(2, arcs(&[heavy, heavy]).into_iter());
+        assert_eq!(graph.unwrap().weight(), 2 * heavy);
+        assert_eq!(
+            Graph::<u32>::from_directed_edges(2, arcs(&[heavy, heavy, 2]).into_iter()).err(),
+            Some(GraphError::WeightOverflow(WeightOverflow))
+        );
+        assert_eq!(
+            Graph::<u32>::from_directed_edges(2, arcs(&[u32::MAX, 1]).into_iter()).err(),
+            Some(GraphError::WeightOverflow(WeightOverflow))
+        );
+        // Loops are dropped and weigh nothing.
+        let loops = [DirectedEdge::Weighted(0, 0, u32::MAX)];
+        assert!(Graph::<u32>::from_directed_edges(1, loops.into_iter()).is_ok());
+
+        let graph = Graph::<u32>::from_directed_edges(2, arcs(&[heavy]).into_iter()).unwrap();
+        assert_eq!(
+            graph.symmetrize(Symmetrize::Sum).unwrap().weight(),
+            2 * heavy
+        );
+        let graph = Graph::<u32>::from_directed_edges(2, arcs(&[heavy + 1]).into_iter()).unwrap();
+        assert_eq!(
+            graph.symmetrize(Symmetrize::Max).err(),
+            Some(SymmetrizeError::WeightOverflow(WeightOverflow))
+        );
+        assert_eq!(graph.symmetrized().weight(), 2 * heavy + 2);
+
+        let arcs = [DirectedEdge::Weighted(0, 1, f64::MAX)];
+        assert!(Graph::<f64>::from_directed_edges(2, arcs.into_iter()).is_err());
+    }
+
+    #[test]
+    fn vertex_out_of_range() {
+        use crate::data_structures::api::GraphError;
+
+        let edges = [(0, 1), (1, 3)].map(DirectedEdge::from);
+        assert_eq!(
+            Graph::<usize>::from_directed_edges(3, edges.into_iter()).err(),
+            Some(GraphError::VertexOutOfRange {
+                vertex: 3,
+                num_vertices: 3
+            })
+        );
+        let edges = [(0, 0)].map(DirectedEdge::from);
+        assert!(Graph::<usize>::from_undirected_edges(0, edges.into_iter()).is_err());
     }
 
     #[test]
@@ -288,12 +817,8 @@ mod tests {
 
         let mut merge_util = MergeUtil::new(6);
 
-        let converter = |edges: Vec<(usize, usize)>| {
-            edges
-                .iter()
-                .map(|edge| Edge::from(edge))
-                .collect::<Vec<Edge>>()
-        };
+        let converter =
+            |edges: Vec<(usize, usize)>| edges.iter().map(Edge::from).collect::<Vec<Edge>>();
 
         let edges1 = converter(vec![(3, 4), (5, 1), (0, 4), (0, 5), (1, 2)]);
         let edges2 = converter(vec![(4, 2), (2, 6), (0, 3), (2, 4), (5, 3)]);
@@ -322,11 +847,237 @@ mod tests {
         ];
 
         let graph: Graph = Graph::from(&adj);
+        let mut rng = rand::thread_rng();
 
-        let contracted = graph.contract_full();
+        let contracted = graph.contract_full(&mut rng);
         assert_eq!(contracted.n, 2);
 
-        let contracted = graph.contract(4);
+        let mut vertex_map = UnionFind::new(6);
+        vertex_map.union(0, 2);
+        vertex_map.union(1, 5);
+        vertex_map.union(1, 4);
+        let (condensed, vertex_map) = graph.condense(vertex_map).into_parts();
+        assert_eq!(vertex_map[..], [0, 1, 0, 2, 1, 1]);
+        assert_eq!(
+            condensed.adj[..],
+            [
+                Node::from(0, &vec![(1, 2), (2, 1)]),
+                Node::from(1, &vec![(0, 2), (2, 2)]),
+                Node::from(2, &vec![(1, 2), (0, 1)]),
+            ]
+        );
+        assert_eq!(condensed.weight(), 10);
+        assert_eq!(graph.symmetrized().weight(), 2 * graph.weight());
+        assert_eq!(graph.reversed().adjacency_list()[3].weight, 3);
+
+        let contracted = graph.contract(4, &mut rng);
         assert_eq!(contracted.n, 4);
+
+        let (contracted, vertex_map) = graph.contract_mapped(3, &mut rng).into_parts();
+        assert_eq!(contracted.n, 3);
+        assert_eq!(vertex_map.len(), 6);
+        assert!(vertex_map.iter().all(|&x| x < 3));
+        for x in 0..3 {
+            assert!(vertex_map.contains(&x));
+        }
+
+        let mut rng1 = StdRng::seed_from_u64(42);
+        let mut rng2 = StdRng::seed_from_u64(42);
+        for t in [2, 3, 5] {
+            let (contracted1, vertex_map1) = graph.contract_mapped(t, &mut rng1).into_parts();
+            let (contracted2, vertex_map2) = graph.contract_mapped(t, &mut rng2).into_parts();
+            assert_eq!(format!("{contracted1:?}"), format!("{contracted2:?}"));
+            assert_eq!(vertex_map1, vertex_map2);
+        }
+    }
+
+    #[test]
+    fn inspect_graph() {
+        let edges = [(0, 1, 3), (1, 2, 1), (2, 3, 3), (3, 0, 1), (0, 2, 2)]
+            .into_iter()
+            .map(DirectedEdge::from);
+        let graph: Graph = Graph::from_undirected_edges(4, edges).unwrap();
+
+        assert_eq!(graph.edges().count(), graph.num_edges());
+        assert!(graph.edges().any(|edge| edge == (2, 0, 2)));
+        let mut neighbors = graph.neighbors(0).collect::<Vec<_>>();
+        neighbors.sort();
+        assert_eq!(neighbors, [(1, 3), (2, 2), (3, 1)]);
+        assert_eq!(graph.weighted_degree(0), 6);
+        assert_eq!(graph.edge_weight(3, 2), Some(3));
+        assert_eq!(graph.edge_weight(1, 3), None);
+
+        // Vertices 1 and 3 both have degree 4.
+        assert_eq!(graph.min_degree_vertex(), Some(1));
+        assert_eq!(graph.cut_weight(&[1]), 8);
+        assert_eq!(graph.cut_weight(&[0, 1]), 2 * 4);
+        assert_eq!(graph.cut_weight(&[]), 0);
+
+        let empty: Graph = Graph::from_directed_edges(0, std::iter::empty()).unwrap();
+        assert_eq!(empty.min_degree_vertex(), None);
+    }
+
+    #[test]
+    fn mutate_graph() {
+        use crate::weight::{Weight, WeightOverflow};
+
+        let sorted_edges = |graph: &Graph| {
+            let mut edges = graph.edges().collect::<Vec<_>>();
+            edges.sort();
+            edges
+        };
+        let edges = [(0, 1, 3), (1, 2, 1), (2, 3, 3), (3, 0, 1)]
+            .into_iter()
+            .map(DirectedEdge::from);
+        let mut graph: Graph = Graph::from_undirected_edges(4, edges).unwrap();
+
+        graph.add_edge(0, 2, 2).unwrap();
+        graph.add_edge(1, 2, 1).unwrap();
+        graph.add_edge(3, 3, 5).unwrap();
+        assert_eq!(graph.edge_weight(2, 1), Some(2));
+        assert_eq!(graph.set_weight(3, 0, 4), Ok(Some(1)));
+        assert_eq!(graph.remove_edge(2, 3), Some(3));
+        assert_eq!(graph.remove_edge(2, 3), None);
+
+        let edges = [(0, 1, 3), (1, 2, 2), (3, 0, 4), (0, 2, 2)]
+            .into_iter()
+            .map(DirectedEdge::from);
+        let expected: Graph = Graph::from_undirected_edges(4, edges).unwrap();
+        assert_eq!(sorted_edges(&graph), sorted_edges(&expected));
+        assert_eq!(graph.weight, expected.weight);
+        assert_eq!(graph.weighted_degree(0), 9);
+
+        assert_eq!(graph.add_vertex(), 4);
+        graph.add_edge(4, 3, 1).unwrap();
+        graph.remove_vertex(1);
+        let edges = [(2, 0, 4), (0, 1, 2), (3, 2, 1)]
+            .into_iter()
+            .map(DirectedEdge::from);
+        let expected: Graph = Graph::from_undirected_edges(4, edges).unwrap();
+        assert_eq!(graph.num_vertices(), 4);
+        assert_eq!(sorted_edges(&graph), sorted_edges(&expected));
+        assert_eq!(graph.weight, expected.weight);
+        assert_eq!(graph.connected_components()[..], [0; 4]);
+
+        let mut graph: Graph<u32> = Graph::from_undirected_edges(2, std::iter::empty()).unwrap();
+        graph.add_edge(0, 1, u32::MAX / 4).unwrap();
+        assert_eq!(graph.add_edge(0, 1, 1), Err(WeightOverflow));
+        assert_eq!(
+            graph.set_weight(0, 1, u32::MAX / 4 + 1),
+            Err(WeightOverflow)
+        );
+        assert_eq!(graph.set_weight(1, 0, 1), Ok(Some(u32::MAX / 4)));
+        assert_eq!(graph.weight(), 2);
+
+        let mut graph: Graph<f64> = Graph::from_undirected_edges(3, std::iter::empty()).unwrap();
+        graph.add_edge(0, 1, 0.1).unwrap();
+        graph.add_edge(1, 2, 0.2).unwrap();
+        graph.set_weight(0, 1, 0.7).unwrap();
+        graph.remove_vertex(2);
+        assert!(graph.weight.approx_eq(1.4) && graph.weight.approx_eq(graph.weight()));
+        assert_eq!(graph.weighted_degree(1), 0.7);
+    }
+
+    #[test]
+    fn mutate_arcs() {
+        use crate::weight::WeightOverflow;
+
+        let edges = [(0, 1, 3), (1, 2, 1)].into_iter().map(DirectedEdge::from);
+        let mut graph: Graph = Graph::from_undirected_edges(3, edges).unwrap();
+
+        graph.add_arc(0, 2, 2).unwrap();
+        assert_eq!(graph.edge_weight(0, 2), Some(2));
+        assert_eq!(graph.edge_weight(2, 0), None);
+        assert_eq!(graph.set_arc_weight(1, 0, 5), Ok(Some(3)));
+        assert_eq!(graph.edge_weight(0, 1), Some(3));
+        assert_eq!(graph.remove_arc(2, 1), Some(1));
+        assert_eq!(graph.edge_weight(1, 2), Some(1));
+        assert_eq!(graph.weight, 3 + 5 + 1 + 2);
+        assert_eq!(graph.weighted_degree(1), 6);
+
+        let violations = graph.symmetry_violations();
+        assert_eq!(violations.len(), 3);
+        graph.add_arc(2, 0, 2).unwrap();
+        graph.set_arc_weight(0, 1, 5).unwrap();
+        graph.add_arc(2, 1, 1).unwrap();
+        assert!(graph.symmetry_violations().is_empty());
+
+        let mut graph: Graph<u32> = Graph::from_undirected_edges(2, std::iter::empty()).unwrap();
+        graph.add_arc(0, 1, u32::MAX / 2).unwrap();
+        assert_eq!(graph.add_arc(1, 0, 1), Err(WeightOverflow));
+        assert_eq!(graph.set_arc_weight(0, 1, 1), Ok(Some(u32::MAX / 2)));
+        assert_eq!(graph.weight, 1);
+    }
+
+    #[test]
+    fn contract_chosen_vertices() {
+        let edges = [
+            (0, 1, 3),
+            (1, 2, 1),
+            (2, 3, 3),
+            (3, 0, 1),
+            (0, 2, 2),
+            (3, 4, 1),
+        ]
+        .into_iter()
+        .map(DirectedEdge::from);
+        let graph: Graph = Graph::from_undirected_edges(5, edges).unwrap();
+
+        let (contracted, vertex_map) = graph.contract_edge(3, 1).into_parts();
+        assert_eq!(vertex_map[..], [0, 1, 2, 1, 3]);
+        assert_eq!(contracted.num_vertices(), 4);
+        assert_eq!(contracted.edge_weight(1, 0), Some(4));
+        assert_eq!(contracted.edge_weight(1, 2), Some(4));
+        assert_eq!(contracted.weight(), graph.weight());
+
+        let (contracted, vertex_map) = graph.contract_vertices(&[4, 2, 0]).into_parts();
+        assert_eq!(vertex_map[..], [0, 1, 0, 2, 0]);
+        assert_eq!(contracted.weight(), graph.weight() - 2 * 2);
+        assert_eq!(
+            contracted.weighted_degree(0),
+            graph.cut_weight(&[0, 2, 4]) / 2
+        );
+        assert!(contracted.symmetry_violations().is_empty());
+
+        let (contracted, vertex_map) = graph.contract_vertices(&[]).into_parts();
+        assert_eq!(vertex_map[..], [0, 1, 2, 3, 4]);
+        assert_eq!(contracted.weight(), graph.weight());
+    }
+
+    #[test]
+    fn disconnected_graph() {
+        let edges = [(0, 3, 2), (3, 0, 2), (1, 4, 1), (4, 1, 1)]
+            .into_iter()
+            .map(DirectedEdge::from);
+        let graph: Graph = Graph::from_directed_edges(6, edges).unwrap();
+        assert_eq!(graph.connected_components()[..], [0, 1, 2, 0, 1, 3]);
+
+        // Contraction merges components only once it runs out of edges.
+        let mut rng = StdRng::seed_from_u64(1);
+        for t in 2..=4 {
+            let (contracted, vertex_map) = graph.contract_mapped(t, &mut rng).into_parts();
+            assert_eq!(contracted.n, t);
+            assert_eq!(vertex_map[0], vertex_map[3]);
+            assert_eq!(vertex_map[1], vertex_map[4]);
+        }
+
+        let edgeless: Graph = Graph::from_directed_edges(3, std::iter::empty()).unwrap();
+        assert_eq!(edgeless.connected_components()[..], [0, 1, 2]);
+        let (contracted, _) = edgeless.contract_mapped(2, &mut rng).into_parts();
+        assert_eq!(contracted.weight(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot contract a graph of 3 vertices to 4, 2 <= t <= 3 is needed")]
+    fn contract_to_more_vertices() {
+        let graph: Graph = Graph::from_directed_edges(3, std::iter::empty()).unwrap();
+        graph.contract_mapped(4, &mut StdRng::seed_from_u64(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot contract a graph of 3 vertices to 1, 2 <= t <= 3 is needed")]
+    fn contract_to_one_vertex() {
+        let graph: Graph = Graph::from_directed_edges(3, std::iter::empty()).unwrap();
+        graph.contract(1, &mut StdRng::seed_from_u64(1));
     }
 }
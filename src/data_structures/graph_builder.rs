@@ -0,0 +1,118 @@
+use crate::data_structures::api::DirectedEdge;
+use crate::data_structures::graph::Graph;
+use crate::weight::{Weight, WeightOverflow};
+
+/// Builds an undirected graph in memory, one edge at a time. Every edge becomes an arc each way,
+/// as the min cut algorithms expect, and parallel edges add up.
+#[derive(Debug, Clone)]
+pub struct GraphBuilder<W = usize> {
+    num_vertices: usize,
+    edges: Vec<(usize, usize, W)>,
+}
+
+impl<W: Weight> GraphBuilder<W> {
+    /// A builder for a graph of vertices `0..num_vertices` without edges yet.
+    pub fn new(num_vertices: usize) -> GraphBuilder<W> {
+        Self::with_capacity(num_vertices, 0)
+    }
+
+    /// Reserves room for `num_edges` edges up front.
+    pub fn with_capacity(num_vertices: usize, num_edges: usize) -> GraphBuilder<W> {
+        Self {
+            num_vertices,
+            edges: Vec::with_capacity(num_edges),
+        }
+    }
+
+    /// The number of vertices of the graph to build.
+    pub fn num_vertices(&self) -> usize {
+        self.num_vertices
+    }
+
+    /// The number of edges added so far, parallel ones included.
+    pub fn num_edges(&self) -> usize {
+        self.edges.len()
+    }
+
+    /// Adds an undirected edge between `v` and `w`.
+    ///
+    /// Panics if an endpoint is not a vertex, or if the weight is negative or not finite.
+    pub fn add_edge(&mut self, v: usize, w: usize, weight: W) -> &mut GraphBuilder<W> {
+        check_edge(self.num_vertices, v, w, weight);
+        self.edges.push((v, w, weight));
+        self
+    }
+
+    /// Builds the graph, with an arc each way for every edge.
+    ///
+    /// Fails when twice the total weight overflows, see `WeightOverflow`.
+    pub fn build(self) -> Result<Graph<W>, WeightOverflow> {
+        let edges = self.edges.into_iter().flat_map(|(v, w, weight)| {
+            [
+                DirectedEdge::Weighted(v, w, weight),
+                DirectedEdge::Weighted(w, v, weight),
+            ]
+        });
+        Graph::from_checked_edges(self.num_vertices, edges)
+    }
+}
+
+pub(crate) fn check_edge<W: Weight>(num_vertices: usize, v: usize, w: usize, weight: W) {
+    for vertex in [v, w] {
+        assert!(
+            vertex < num_vertices,
+            "vertex {vertex} is out of range for a graph of {num_vertices} vertices"
+        );
+    }
+    assert!(
+        weight >= W::ZERO && weight.is_finite(),
+        "the weight {weight} of edge {v} - {w} is negative or not finite"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data_structures::graph::Graph;
+    use crate::data_structures::graph_builder::GraphBuilder;
+    use crate::min_cut_algo::MinCutAlgo;
+    use crate::stoer_wagner::StoerWagnerAlgo;
+    use crate::traits::EdgeWeightedGraph;
+    use crate::weight::WeightOverflow;
+
+    #[test]
+    fn build_graph() {
+        let mut builder = GraphBuilder::with_capacity(4, 5);
+        builder
+            .add_edge(0, 1, 3)
+            .add_edge(1, 2, 1)
+            .add_edge(2, 3, 3)
+            .add_edge(3, 0, 1)
+            .add_edge(2, 1, 1);
+        assert_eq!((builder.num_vertices(), builder.num_edges()), (4, 5));
+
+        let graph: Graph = builder.build().unwrap();
+        assert_eq!(graph.num_vertices(), 4);
+        assert_eq!(graph.weight(), 18);
+        assert!(graph.symmetry_violations().is_empty());
+
+        let min_cut = StoerWagnerAlgo::new(&graph).execute(false).unwrap();
+        assert_eq!(min_cut.value(), 6);
+        assert_eq!(min_cut.partition(), (&[0, 1][..], &[2, 3][..]));
+
+        let mut builder = GraphBuilder::new(2);
+        builder.add_edge(0, 1, u32::MAX / 2 + 1);
+        assert_eq!(builder.build().unwrap_err(), WeightOverflow);
+    }
+
+    #[test]
+    #[should_panic(expected = "vertex 2 is out of range for a graph of 2 vertices")]
+    fn vertex_out_of_range() {
+        GraphBuilder::new(2).add_edge(0, 2, 1.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "is negative or not finite")]
+    fn invalid_weight() {
+        GraphBuilder::new(2).add_edge(0, 1, f64::NAN);
+    }
+}
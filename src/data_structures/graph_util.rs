@@ -1,14 +1,15 @@
 use std::borrow::Borrow;
-use std::rc::Rc;
+
+use crate::weight::Weight;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
-pub struct Edge {
+pub struct Edge<W = usize> {
     pub other: usize,
-    pub weight: usize,
+    pub weight: W,
 }
 
-impl From<&(usize, usize)> for Edge {
-    fn from(edge: &(usize, usize)) -> Self {
+impl<W: Copy> From<&(usize, W)> for Edge<W> {
+    fn from(edge: &(usize, W)) -> Self {
         Edge {
             other: edge.0,
             weight: edge.1,
@@ -17,33 +18,33 @@ impl From<&(usize, usize)> for Edge {
 }
 
 #[derive(Debug, Clone, PartialEq)]
-pub struct Node {
+pub struct Node<W = usize> {
     pub vertex: usize,
-    pub weight: usize,
-    pub edges: Rc<[Edge]>,
+    pub weight: W,
+    pub edges: Vec<Edge<W>>,
 }
 
-impl Node {
-    pub fn from(vertex: usize, edges: &impl Borrow<[(usize, usize)]>) -> Self {
+impl<W: Weight> Node<W> {
+    pub fn from(vertex: usize, edges: &impl Borrow<[(usize, W)]>) -> Self {
         Self {
             vertex,
             weight: edges.borrow().iter().map(|edge| edge.1).sum(),
-            edges: edges.borrow().iter().map(|edge| Edge::from(edge)).collect(),
+            edges: edges.borrow().iter().map(Edge::from).collect(),
         }
     }
 }
 
 #[derive(Debug)]
-pub struct MergeUtil {
+pub struct MergeUtil<W = usize> {
     pub stack: Box<[usize]>,
-    pub merge_proxy: Box<[usize]>,
+    pub merge_proxy: Box<[W]>,
 }
 
-impl MergeUtil {
+impl<W: Weight> MergeUtil<W> {
     pub fn new(n: usize) -> Self {
         MergeUtil {
             stack: vec![0; n].into_boxed_slice(),
-            merge_proxy: vec![0; n].into_boxed_slice(),
+            merge_proxy: vec![W::ZERO; n].into_boxed_slice(),
         }
     }
 }
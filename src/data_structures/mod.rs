@@ -1,5 +1,8 @@
 pub mod api;
+pub mod contraction;
 mod fenwick_tree;
 pub mod graph;
+pub mod graph_builder;
 pub(crate) mod graph_util;
-mod union_find;
+pub(crate) mod segment_tree;
+pub(crate) mod union_find;
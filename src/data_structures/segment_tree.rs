@@ -0,0 +1,118 @@
+use crate::weight::SignedWeight;
+
+pub struct MinSegmentTree<S> {
+    n: usize,
+    min: Box<[(S, usize)]>,
+    lazy: Box<[S]>,
+}
+
+// The smaller value, ties going to the smaller index.
+fn min<S: SignedWeight>(a: (S, usize), b: (S, usize)) -> (S, usize) {
+    if b.0 < a.0 || (b.0 == a.0 && b.1 < a.1) {
+        b
+    } else {
+        a
+    }
+}
+
+impl<S: SignedWeight> MinSegmentTree<S> {
+    pub fn from(arr: &[S]) -> Self {
+        let n = arr.len();
+        let mut tree = MinSegmentTree {
+            n,
+            min: vec![(S::MAX, 0); 4 * n.max(1)].into_boxed_slice(),
+            lazy: vec![S::ZERO; 4 * n.max(1)].into_boxed_slice(),
+        };
+        if n > 0 {
+            tree.build(1, 0, n - 1, arr);
+        }
+        tree
+    }
+
+    fn build(&mut self, node: usize, start: usize, end: usize, arr: &[S]) {
+        if start == end {
+            self.min[node] = (arr[start], start);
+            return;
+        }
+        let mid = (start + end) >> 1;
+        self.build(2 * node, start, mid, arr);
+        self.build(2 * node + 1, mid + 1, end, arr);
+        self.min[node] = min(self.min[2 * node], self.min[2 * node + 1]);
+    }
+
+    pub fn add(&mut self, l: usize, r: usize, val: S) {
+        if l <= r {
+            self.add_rec(1, 0, self.n - 1, l, r, val);
+        }
+    }
+
+    fn add_rec(&mut self, node: usize, start: usize, end: usize, l: usize, r: usize, val: S) {
+        if r < start || end < l {
+            return;
+        }
+        if l <= start && end <= r {
+            self.min[node].0 = self.min[node].0.saturating_add(val);
+            self.lazy[node] += val;
+            return;
+        }
+        let mid = (start + end) >> 1;
+        self.add_rec(2 * node, start, mid, l, r, val);
+        self.add_rec(2 * node + 1, mid + 1, end, l, r, val);
+        let (min, arg) = min(self.min[2 * node], self.min[2 * node + 1]);
+        self.min[node] = (min.saturating_add(self.lazy[node]), arg);
+    }
+
+    pub fn min(&self, l: usize, r: usize) -> (S, usize) {
+        if l > r {
+            return (S::MAX, l);
+        }
+        self.min_rec(1, 0, self.n - 1, l, r)
+    }
+
+    fn min_rec(&self, node: usize, start: usize, end: usize, l: usize, r: usize) -> (S, usize) {
+        if r < start || end < l {
+            return (S::MAX, start);
+        }
+        if l <= start && end <= r {
+            return self.min[node];
+        }
+        let mid = (start + end) >> 1;
+        let (min, arg) = min(
+            self.min_rec(2 * node, start, mid, l, r),
+            self.min_rec(2 * node + 1, mid + 1, end, l, r),
+        );
+        (min.saturating_add(self.lazy[node]), arg)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data_structures::segment_tree::MinSegmentTree;
+
+    #[test]
+    fn test_min_segment_tree() {
+        let mut tree = MinSegmentTree::<i64>::from(&[5, 3, 8, 6, 1, 9, 4]);
+        assert_eq!(tree.min(0, 6), (1, 4));
+        assert_eq!(tree.min(0, 3), (3, 1));
+        assert_eq!(tree.min(5, 6), (4, 6));
+        assert_eq!(tree.min(2, 2), (8, 2));
+
+        tree.add(3, 5, 10);
+        assert_eq!(tree.min(0, 6), (3, 1));
+        assert_eq!(tree.min(3, 6), (4, 6));
+        assert_eq!(tree.min(4, 4), (11, 4));
+
+        tree.add(0, 2, -4);
+        assert_eq!(tree.min(0, 6), (-1, 1));
+        assert_eq!(tree.min(2, 5), (4, 2));
+
+        tree.add(3, 5, -10);
+        tree.add(0, 2, 4);
+        assert_eq!(tree.min(0, 6), (1, 4));
+        assert_eq!(tree.min(0, 1), (3, 1));
+
+        let tree = MinSegmentTree::from(&[i64::MAX, 2, i64::MAX]);
+        assert_eq!(tree.min(0, 0), (i64::MAX, 0));
+        assert_eq!(tree.min(0, 2), (2, 1));
+    }
+}
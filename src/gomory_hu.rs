@@ -0,0 +1,322 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use crate::data_structures::graph::Graph;
+use crate::max_flow::DinicAlgo;
+use crate::min_cut_algo::MinCutResult;
+use crate::traits::EdgeWeightedGraph;
+use crate::weight::Weight;
+
+/// A Gomory-Hu cut tree: the min cut between two vertices weighs as much as the lightest edge on
+/// their tree path, and removing that edge splits the tree into the two sides of such a cut. Every
+/// vertex but the root 0 has an edge to its parent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GomoryHuTree<W = usize> {
+    parent: Box<[usize]>,
+    weight: Box<[W]>,
+    depth: Box<[usize]>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseGomoryHuTreeError {
+    MissingHeader,
+    InvalidLine { line: usize },
+    VertexOutOfRange { line: usize, vertex: usize },
+    DuplicateVertex { line: usize, vertex: usize },
+    MissingVertex { vertex: usize },
+    Cycle { vertex: usize },
+}
+
+impl Display for ParseGomoryHuTreeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseGomoryHuTreeError::MissingHeader => write!(f, "missing vertex count header"),
+            ParseGomoryHuTreeError::InvalidLine { line } => {
+                write!(f, "{line}: expected a vertex, its parent and a weight")
+            }
+            ParseGomoryHuTreeError::VertexOutOfRange { line, vertex } => {
+                write!(f, "{line}: vertex {vertex} is out of range")
+            }
+            ParseGomoryHuTreeError::DuplicateVertex { line, vertex } => {
+                write!(f, "{line}: vertex {vertex} already has a parent")
+            }
+            ParseGomoryHuTreeError::MissingVertex { vertex } => {
+                write!(f, "vertex {vertex} has no parent")
+            }
+            ParseGomoryHuTreeError::Cycle { vertex } => {
+                write!(f, "vertex {vertex} is on a cycle")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseGomoryHuTreeError {}
+
+impl<W: Weight> GomoryHuTree<W> {
+    /// Gusfield's algorithm, with one max-flow per vertex but the root and no contraction. The
+    /// capacity between two vertices is the weight of the arcs between them in either direction.
+    pub fn new(graph: &Graph<W>) -> GomoryHuTree<W> {
+        let n = graph.num_vertices();
+        let symmetrized = graph.symmetrized();
+        let dinic = DinicAlgo::new(&symmetrized);
+        let mut parent = vec![0; n];
+        let mut weight = vec![W::ZERO; n];
+
+        for s in 1..n {
+            let t = parent[s];
+            let max_flow = dinic.max_flow(s, t);
+            let mut source_side = vec![false; n];
+            for &v in max_flow.partition().0 {
+                source_side[v] = true;
+            }
+
+            weight[s] = max_flow.value();
+            for v in 0..n {
+                if v != s && source_side[v] && parent[v] == t {
+                    parent[v] = s;
+                }
+            }
+            if t != 0 && source_side[parent[t]] {
+                parent[s] = parent[t];
+                parent[t] = s;
+                weight[s] = weight[t];
+                weight[t] = max_flow.value();
+            }
+        }
+
+        Self::from_parents(parent.into_boxed_slice(), weight.into_boxed_slice())
+            .expect("Gusfield's algorithm builds a tree")
+    }
+
+    fn from_parents(
+        parent: Box<[usize]>,
+        weight: Box<[W]>,
+    ) -> Result<GomoryHuTree<W>, ParseGomoryHuTreeError> {
+        let n = parent.len();
+        let mut depth = vec![usize::MAX; n].into_boxed_slice();
+        if n > 0 {
+            depth[0] = 0;
+        }
+        for v in 0..n {
+            let mut path = Vec::new();
+            let mut u = v;
+            while depth[u] == usize::MAX {
+                if path.len() > n {
+                    return Err(ParseGomoryHuTreeError::Cycle { vertex: v });
+                }
+                path.push(u);
+                u = parent[u];
+            }
+            for &w in path.iter().rev() {
+                depth[w] = depth[parent[w]] + 1;
+            }
+        }
+        Ok(GomoryHuTree {
+            parent,
+            weight,
+            depth,
+        })
+    }
+
+    pub fn num_vertices(&self) -> usize {
+        self.parent.len()
+    }
+
+    /// The tree edges as (vertex, parent, weight).
+    pub fn edges(&self) -> impl Iterator<Item = (usize, usize, W)> + '_ {
+        (1..self.num_vertices()).map(|v| (v, self.parent[v], self.weight[v]))
+    }
+
+    // The vertex whose edge to its parent is the lightest on the path between `u` and `v`.
+    fn lightest_edge(&self, mut u: usize, mut v: usize) -> usize {
+        assert!(u != v);
+        let mut lightest = None;
+        let mut visit = |w: usize| {
+            if lightest.map_or(true, |l| self.weight[w] < self.weight[l]) {
+                lightest = Some(w);
+            }
+        };
+        while u != v {
+            if self.depth[u] >= self.depth[v] {
+                visit(u);
+                u = self.parent[u];
+            } else {
+                visit(v);
+                v = self.parent[v];
+            }
+        }
+        lightest.unwrap()
+    }
+
+    /// The weight of a min cut between `u` and `v`.
+    pub fn connectivity(&self, u: usize, v: usize) -> W {
+        self.weight[self.lightest_edge(u, v)]
+    }
+
+    /// The sides of a min cut between `u` and `v`, the one holding `u` first.
+    pub fn partition(&self, u: usize, v: usize) -> (Box<[usize]>, Box<[usize]>) {
+        let sides = self.sides(self.lightest_edge(u, v));
+        let (first, second): (Vec<_>, Vec<_>) =
+            (0..self.num_vertices()).partition(|&w| sides[w] == sides[u]);
+        (first.into_boxed_slice(), second.into_boxed_slice())
+    }
+
+    pub fn min_cut(&self, graph: &Graph<W>, u: usize, v: usize) -> MinCutResult<W> {
+        MinCutResult::from_sides(graph, &self.sides(self.lightest_edge(u, v)))
+    }
+
+    // Whether each vertex lies in the subtree of `root`.
+    fn sides(&self, root: usize) -> Box<[usize]> {
+        (0..self.num_vertices())
+            .map(|mut w| {
+                while self.depth[w] > self.depth[root] {
+                    w = self.parent[w];
+                }
+                (w == root) as usize
+            })
+            .collect()
+    }
+}
+
+// A header with the number of vertices, then a line per vertex but the root with the vertex, its
+// parent and the weight of the edge between them.
+impl<W: Weight> Display for GomoryHuTree<W> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}", self.num_vertices())?;
+        for (v, parent, weight) in self.edges() {
+            writeln!(f, "{v} {parent} {weight}")?;
+        }
+        Ok(())
+    }
+}
+
+impl<W: Weight> FromStr for GomoryHuTree<W> {
+    type Err = ParseGomoryHuTreeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty());
+        let n = lines
+            .next()
+            .and_then(|(_, line)| line.parse::<usize>().ok())
+            .ok_or(ParseGomoryHuTreeError::MissingHeader)?;
+
+        let mut parent = vec![usize::MAX; n].into_boxed_slice();
+        let mut weight = vec![W::ZERO; n].into_boxed_slice();
+        if n > 0 {
+            parent[0] = 0;
+        }
+        for (line, text) in lines {
+            let fields = text.split_whitespace().collect::<Vec<_>>();
+            let numbers = match fields[..] {
+                [v, p, w] => v.parse::<usize>().ok().zip(p.parse::<usize>().ok()).zip(
+                    w.parse::<W>()
+                        .ok()
+                        .filter(|&w| w >= W::ZERO && w.is_finite()),
+                ),
+                _ => None,
+            };
+            let Some(((v, p), w)) = numbers else {
+                return Err(ParseGomoryHuTreeError::InvalidLine { line });
+            };
+            for vertex in [v, p] {
+                if vertex >= n {
+                    return Err(ParseGomoryHuTreeError::VertexOutOfRange { line, vertex });
+                }
+            }
+            if v == 0 || parent[v] != usize::MAX {
+                return Err(ParseGomoryHuTreeError::DuplicateVertex { line, vertex: v });
+            }
+            parent[v] = p;
+            weight[v] = w;
+        }
+        if let Some(vertex) = parent.iter().position(|&p| p == usize::MAX) {
+            return Err(ParseGomoryHuTreeError::MissingVertex { vertex });
+        }
+        Self::from_parents(parent, weight)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data_structures::graph::Graph;
+    use crate::gomory_hu::{GomoryHuTree, ParseGomoryHuTreeError};
+    use crate::max_flow::DinicAlgo;
+    use crate::traits::EdgeWeightedGraph;
+
+    #[test]
+    fn gomory_hu_tree_test() {
+        use crate::data_structures::api::DirectedEdge;
+
+        let edges = [
+            (0, 1, 1),
+            (0, 2, 7),
+            (1, 2, 1),
+            (1, 3, 3),
+            (1, 4, 2),
+            (2, 4, 4),
+            (3, 4, 1),
+            (3, 5, 6),
+            (4, 5, 2),
+        ][..]
+            .iter()
+            .map(|it| DirectedEdge::from(*it));
+        let graph: Graph = Graph::from_directed_edges(6, edges).unwrap();
+
+        let tree = GomoryHuTree::new(&graph);
+        let symmetrized = graph.symmetrized();
+        let dinic = DinicAlgo::new(&symmetrized);
+        for u in 0..6 {
+            for v in u + 1..6 {
+                let expected = dinic.max_flow(u, v).value();
+                assert_eq!(tree.connectivity(u, v), expected, "{u} {v}");
+                assert_eq!(tree.connectivity(v, u), expected, "{v} {u}");
+
+                let min_cut = tree.min_cut(&graph, u, v);
+                assert_eq!(min_cut.value(), expected, "{u} {v}");
+                let (first, second) = tree.partition(u, v);
+                assert!(first.contains(&u) && second.contains(&v));
+            }
+        }
+
+        let text = tree.to_string();
+        assert_eq!(text.lines().count(), 6);
+        assert_eq!(text.parse::<GomoryHuTree>(), Ok(tree));
+    }
+
+    #[test]
+    fn parse_gomory_hu_tree_test() {
+        let tree = "3\n1 0 4\n2 1 5\n".parse::<GomoryHuTree>().unwrap();
+        assert_eq!(tree.connectivity(0, 2), 4);
+        assert_eq!(tree.connectivity(1, 2), 5);
+        assert_eq!(tree.partition(1, 2), (vec![0, 1].into(), vec![2].into()));
+
+        assert_eq!(
+            "".parse::<GomoryHuTree>(),
+            Err(ParseGomoryHuTreeError::MissingHeader)
+        );
+        assert_eq!(
+            "3\n1 0\n".parse::<GomoryHuTree>(),
+            Err(ParseGomoryHuTreeError::InvalidLine { line: 2 })
+        );
+        assert_eq!(
+            "3\n1 3 4\n".parse::<GomoryHuTree>(),
+            Err(ParseGomoryHuTreeError::VertexOutOfRange { line: 2, vertex: 3 })
+        );
+        assert_eq!(
+            "3\n1 0 4\n1 2 5\n".parse::<GomoryHuTree>(),
+            Err(ParseGomoryHuTreeError::DuplicateVertex { line: 3, vertex: 1 })
+        );
+        assert_eq!(
+            "3\n1 0 4\n".parse::<GomoryHuTree>(),
+            Err(ParseGomoryHuTreeError::MissingVertex { vertex: 2 })
+        );
+        assert_eq!(
+            "3\n1 2 4\n2 1 5\n".parse::<GomoryHuTree>(),
+            Err(ParseGomoryHuTreeError::Cycle { vertex: 1 })
+        );
+    }
+}
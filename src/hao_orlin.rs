@@ -0,0 +1,355 @@
+use crate::data_structures::graph::Graph;
+use crate::min_cut_algo::{
+    check_num_vertices, DirectedMinCutAlgo, DirectedMinCutResult, MinCutError,
+};
+use crate::traits::EdgeWeightedGraph;
+use crate::weight::Weight;
+
+// Hao and Orlin's directed min cut, in the time of a single push-relabel max-flow per direction.
+// Every vertex but vertex 0 is a sink once, and becomes a source after its phase; the cuts whose
+// source side holds vertex 0 are found on the graph, the others on its reverse. The awake vertices
+// are kept in buckets by label and the cut is updated as vertices fall asleep or wake up, so that
+// beyond its pushes and relabels a phase only pays for the arcs of the vertices changing sides and
+// a scan of the labels.
+pub struct HaoOrlinAlgo<'a, W = usize> {
+    graph: &'a Graph<W>,
+}
+
+impl<'a, W: Weight> HaoOrlinAlgo<'a, W> {
+    pub fn new(graph: &'a Graph<W>) -> HaoOrlinAlgo<'a, W> {
+        Self { graph }
+    }
+}
+
+impl<W: Weight> DirectedMinCutAlgo<W> for HaoOrlinAlgo<'_, W> {
+    fn graph(&self) -> &Graph<W> {
+        self.graph
+    }
+
+    fn directed_min_cut(&self) -> Result<DirectedMinCutResult<W>, MinCutError> {
+        check_num_vertices(self.graph)?;
+        let (out_value, out_side) = PreflowNetwork::new(self.graph).min_source_side_cut(0);
+        let reversed = self.graph.reversed();
+        let (in_value, in_side) = PreflowNetwork::new(&reversed).min_source_side_cut(0);
+
+        let source_side = if out_value <= in_value {
+            out_side
+        } else {
+            in_side.iter().map(|&side| !side).collect()
+        };
+        Ok(DirectedMinCutResult::from_source_side(
+            self.graph,
+            &source_side,
+        ))
+    }
+}
+
+// Arcs are stored in pairs, the reverse of arc `e` being `e ^ 1`. The vertices that are neither
+// sources nor awake lie in a stack of dormant sets, with no residual arc from a set to a later
+// one or to the awake vertices.
+struct PreflowNetwork<W> {
+    to: Vec<usize>,
+    capacity: Vec<W>,
+    // The weight of the arc of the graph each pair stands for.
+    weight: Vec<W>,
+    arcs: Box<[Vec<usize>]>,
+    excess: Box<[W]>,
+    label: Box<[usize]>,
+    next_arc: Box<[usize]>,
+    source: Box<[bool]>,
+    awake: Box<[bool]>,
+    dormant: Vec<Vec<usize>>,
+    // The awake vertices by label, with the position of each in its bucket.
+    buckets: Vec<Vec<usize>>,
+    position: Box<[usize]>,
+    // The awake vertices that may hold excess.
+    active: Vec<usize>,
+    // The weight of the arcs from the vertices that are not awake to those that are.
+    cut: W,
+}
+
+impl<W: Weight> PreflowNetwork<W> {
+    fn new(graph: &Graph<W>) -> Self {
+        let n = graph.num_vertices();
+        let mut network = PreflowNetwork {
+            to: Vec::with_capacity(2 * graph.num_edges()),
+            capacity: Vec::with_capacity(2 * graph.num_edges()),
+            weight: Vec::with_capacity(graph.num_edges()),
+            arcs: vec![Vec::new(); n].into_boxed_slice(),
+            excess: vec![W::ZERO; n].into_boxed_slice(),
+            label: vec![0; n].into_boxed_slice(),
+            next_arc: vec![0; n].into_boxed_slice(),
+            source: vec![false; n].into_boxed_slice(),
+            awake: vec![true; n].into_boxed_slice(),
+            dormant: Vec::new(),
+            buckets: vec![(0..n).collect()],
+            position: (0..n).collect(),
+            active: Vec::new(),
+            cut: W::ZERO,
+        };
+        for node in graph.adjacency_list() {
+            for edge in node.edges.iter() {
+                network.arcs[node.vertex].push(network.to.len());
+                network.to.push(edge.other);
+                network.capacity.push(edge.weight);
+                network.arcs[edge.other].push(network.to.len());
+                network.to.push(node.vertex);
+                network.capacity.push(W::ZERO);
+                network.weight.push(edge.weight);
+            }
+        }
+        network
+    }
+
+    // The lightest cut whose source side holds `s`, with the source side.
+    fn min_source_side_cut(mut self, s: usize) -> (W, Box<[bool]>) {
+        let mut best = (W::MAX, Box::default());
+        self.make_source(s);
+
+        loop {
+            let Some(t) = self
+                .buckets
+                .iter()
+                .find_map(|bucket| bucket.first().copied())
+            else {
+                let Some(set) = self.dormant.pop() else {
+                    return best;
+                };
+                for v in set {
+                    self.wake(v);
+                }
+                continue;
+            };
+            while let Some(v) = self.active.pop() {
+                if v != t {
+                    self.discharge(v, t);
+                }
+            }
+
+            if self.cut < best.0 {
+                best = (self.cut, self.awake.iter().map(|&awake| !awake).collect());
+            }
+            self.make_source(t);
+        }
+    }
+
+    // Turns `v` into a source, saturating every arc it has to a vertex that is not one.
+    fn make_source(&mut self, v: usize) {
+        self.sleep(v);
+        self.source[v] = true;
+        for i in 0..self.arcs[v].len() {
+            let e = self.arcs[v][i];
+            let w = self.to[e];
+            if !self.source[w] {
+                let flow = self.capacity[e];
+                self.capacity[e] = W::ZERO;
+                self.capacity[e ^ 1] += flow;
+                if self.awake[w] && self.excess[w] == W::ZERO {
+                    self.active.push(w);
+                }
+                self.excess[w] += flow;
+            }
+        }
+    }
+
+    fn wake(&mut self, v: usize) {
+        self.set_awake(v, true);
+        self.insert(v);
+        self.next_arc[v] = 0;
+        if self.excess[v] > W::ZERO {
+            self.active.push(v);
+        }
+    }
+
+    fn sleep(&mut self, v: usize) {
+        if self.awake[v] {
+            self.remove(v);
+            self.set_awake(v, false);
+        }
+    }
+
+    // Updates the cut for the arcs of `v`, the only ones to cross it or not as `v` wakes or sleeps.
+    fn set_awake(&mut self, v: usize, awake: bool) {
+        for i in 0..self.arcs[v].len() {
+            if self.crosses(self.arcs[v][i]) {
+                self.cut -= self.weight[self.arcs[v][i] / 2];
+            }
+        }
+        self.awake[v] = awake;
+        for i in 0..self.arcs[v].len() {
+            if self.crosses(self.arcs[v][i]) {
+                self.cut += self.weight[self.arcs[v][i] / 2];
+            }
+        }
+    }
+
+    // Whether the arc of the graph that the pair of `e` stands for leaves a vertex that is not
+    // awake for one that is.
+    fn crosses(&self, e: usize) -> bool {
+        let (tail, head) = (self.to[e | 1], self.to[e & !1]);
+        !self.awake[tail] && self.awake[head]
+    }
+
+    fn insert(&mut self, v: usize) {
+        let label = self.label[v];
+        if label >= self.buckets.len() {
+            self.buckets.resize(label + 1, Vec::new());
+        }
+        self.position[v] = self.buckets[label].len();
+        self.buckets[label].push(v);
+    }
+
+    fn remove(&mut self, v: usize) {
+        let bucket = &mut self.buckets[self.label[v]];
+        bucket.swap_remove(self.position[v]);
+        if let Some(&u) = bucket.get(self.position[v]) {
+            self.position[u] = self.position[v];
+        }
+    }
+
+    // Pushes the excess of `v` towards the sink `t` until none is left or `v` falls asleep.
+    fn discharge(&mut self, v: usize, t: usize) {
+        while self.awake[v] && self.excess[v] > W::ZERO {
+            let Some(&e) = self.arcs[v].get(self.next_arc[v]) else {
+                self.relabel(v);
+                continue;
+            };
+            let w = self.to[e];
+            if self.capacity[e] > W::ZERO && self.awake[w] && self.label[v] == self.label[w] + 1 {
+                let flow = if self.excess[v] < self.capacity[e] {
+                    self.excess[v]
+                } else {
+                    self.capacity[e]
+                };
+                self.capacity[e] -= flow;
+                self.capacity[e ^ 1] += flow;
+                self.excess[v] -= flow;
+                if w != t && self.excess[w] == W::ZERO {
+                    self.active.push(w);
+                }
+                self.excess[w] += flow;
+            } else {
+                self.next_arc[v] += 1;
+            }
+        }
+    }
+
+    fn relabel(&mut self, v: usize) {
+        let label = self.label[v];
+        if self.buckets[label].len() == 1 {
+            // No awake vertex at or above the label of `v` can reach the sink any more.
+            let set = self.buckets[label..].concat();
+            for &u in set.iter() {
+                self.sleep(u);
+            }
+            self.dormant.push(set);
+            return;
+        }
+
+        let min_label = self.arcs[v]
+            .iter()
+            .filter(|&&e| self.capacity[e] > W::ZERO && self.awake[self.to[e]])
+            .map(|&e| self.label[self.to[e]])
+            .min();
+        match min_label {
+            Some(min_label) => {
+                self.remove(v);
+                self.label[v] = min_label + 1;
+                self.insert(v);
+                self.next_arc[v] = 0;
+            }
+            None => {
+                self.sleep(v);
+                self.dormant.push(vec![v]);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    use crate::data_structures::api::DirectedEdge;
+    use crate::data_structures::graph::Graph;
+    use crate::hao_orlin::HaoOrlinAlgo;
+    use crate::max_flow::DinicAlgo;
+    use crate::min_cut_algo::{DirectedMinCutAlgo, MinCutAlgo};
+    use crate::stoer_wagner::StoerWagnerAlgo;
+    use crate::traits::EdgeWeightedGraph;
+
+    #[test]
+    fn hao_orlin_test() {
+        let edges = [
+            (0, 1, 10),
+            (1, 2, 10),
+            (2, 3, 10),
+            (3, 0, 3),
+            (1, 0, 1),
+            (2, 0, 1),
+            (3, 1, 4),
+        ][..]
+            .iter()
+            .map(|it| DirectedEdge::from(*it));
+        let graph: Graph = Graph::from_directed_edges(4, edges).unwrap();
+
+        let min_cut = HaoOrlinAlgo::new(&graph).directed_min_cut().unwrap();
+        assert_eq!(min_cut.value(), 5);
+        assert_eq!(min_cut.partition(), (&[1, 2, 3][..], &[0][..]));
+        assert_eq!(min_cut.crossing_arcs(), [(1, 0, 1), (2, 0, 1), (3, 0, 3)]);
+    }
+
+    #[test]
+    fn hao_orlin_test_random() {
+        let mut rng = StdRng::seed_from_u64(7);
+        for _ in 0..50 {
+            let n = rng.gen_range(2..9);
+            let edges = (0..rng.gen_range(0..4 * n))
+                .map(|_| {
+                    let (v, w) = (rng.gen_range(0..n), rng.gen_range(0..n));
+                    DirectedEdge::Weighted(v, w, rng.gen_range(1..10))
+                })
+                .collect::<Vec<_>>();
+            let graph: Graph = Graph::from_directed_edges(n, edges.into_iter()).unwrap();
+
+            let expected = (1..(1 << n) - 1)
+                .map(|mask: usize| {
+                    graph
+                        .adjacency_list()
+                        .iter()
+                        .filter(|node| mask >> node.vertex & 1 == 1)
+                        .flat_map(|node| node.edges.iter())
+                        .filter(|edge| mask >> edge.other & 1 == 0)
+                        .map(|edge| edge.weight)
+                        .sum::<usize>()
+                })
+                .min()
+                .unwrap();
+            let min_cut = HaoOrlinAlgo::new(&graph).directed_min_cut().unwrap();
+            assert_eq!(min_cut.value(), expected);
+            assert_eq!(
+                DinicAlgo::new(&graph).directed_min_cut().unwrap().value(),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn hao_orlin_test_files() {
+        for input in [
+            "src/files/input_random_1_6.txt",
+            "src/files/input_random_10_25.txt",
+            "src/files/input_random_20_75.txt",
+        ] {
+            let graph = crate::utils::read_graph(input).unwrap();
+            let min_cut = HaoOrlinAlgo::new(&graph).directed_min_cut().unwrap();
+            // The files list every edge in both directions.
+            assert_eq!(
+                2 * min_cut.value(),
+                StoerWagnerAlgo::new(&graph).execute(false).unwrap().value(),
+                "{input}"
+            );
+        }
+    }
+}
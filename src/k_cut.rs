@@ -0,0 +1,386 @@
+use std::sync::Mutex;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::data_structures::graph::Graph;
+pub use crate::min_cut_algo::KCutResult;
+use crate::min_cut_algo::{check_num_vertices, MinCutError, TrialPlanning};
+use crate::traits::{EdgeWeightedGraph, MinCutGraph};
+use crate::weight::Weight;
+
+const LEAF_VERTICES: usize = 10;
+
+pub trait KCutAlgo<W: Weight = usize>: TrialPlanning {
+    fn graph(&self) -> &Graph<W>;
+
+    fn k(&self) -> usize;
+
+    fn trial(&self, rng: &mut StdRng) -> KCutResult<W>;
+
+    /// Every entry point fails with this error on a graph of fewer than `k` vertices, and returns
+    /// a k-cut of weight 0 along the components of a graph of at least `k` of them without running
+    /// any trial.
+    fn check_graph(&self) -> Result<(), MinCutError> {
+        check_num_vertices(self.graph())?;
+        let num_vertices = self.graph().num_vertices();
+        if num_vertices < self.k() {
+            return Err(MinCutError::TooFewVerticesForKCut {
+                num_vertices,
+                k: self.k(),
+            });
+        }
+        Ok(())
+    }
+
+    fn iterate(&self) -> Result<KCutResult<W>, MinCutError> {
+        if let Some(k_cut) = trivial_k_cut(self)? {
+            return Ok(k_cut);
+        }
+        Ok(self.trial(&mut self.trial_rng()))
+    }
+
+    fn iterate_n(&self, n: usize) -> Result<KCutResult<W>, MinCutError> {
+        if let Some(k_cut) = trivial_k_cut(self)? {
+            return Ok(k_cut);
+        }
+        let mut rng = self.trial_rng();
+        let mut k_cut = self.trial(&mut rng);
+        for _ in 1..n {
+            let new_k_cut = self.trial(&mut rng);
+            if new_k_cut.value() < k_cut.value() {
+                k_cut = new_k_cut;
+            }
+        }
+        Ok(k_cut)
+    }
+
+    fn iterate_success_lower_bound(&self, prob: f64) -> Result<KCutResult<W>, MinCutError> {
+        self.iterate_n(self.min_num_trials(prob))
+    }
+
+    fn execute(&self) -> Result<KCutResult<W>, MinCutError> {
+        let expected_lower_bound = 1f64 - 1f64 / self.graph().num_vertices() as f64;
+        self.iterate_success_lower_bound(expected_lower_bound)
+    }
+}
+
+// Contracts the graph down to 2(k - 1) vertices, which keeps a fixed min k-cut with probability at
+// least 1 / C(|V|, 2(k - 1)), then tries every partition of the contracted graph into k parts.
+pub struct KargerKCut<'a, W = usize> {
+    graph: &'a Graph<W>,
+    k: usize,
+    rng: Mutex<StdRng>,
+}
+
+impl<'a, W: Weight> KargerKCut<'a, W> {
+    pub fn new(graph: &'a Graph<W>, k: usize) -> KargerKCut<'a, W> {
+        Self::from_rng(graph, k, StdRng::from_entropy())
+    }
+
+    pub fn with_seed(graph: &'a Graph<W>, k: usize, seed: u64) -> KargerKCut<'a, W> {
+        Self::from_rng(graph, k, StdRng::seed_from_u64(seed))
+    }
+
+    fn from_rng(graph: &'a Graph<W>, k: usize, rng: StdRng) -> KargerKCut<'a, W> {
+        assert!(k >= 2, "a k-cut needs k >= 2 parts, not {k}");
+        Self {
+            graph,
+            k,
+            rng: Mutex::new(rng),
+        }
+    }
+
+    fn contracted_vertices(&self) -> usize {
+        std::cmp::min(self.graph.num_vertices(), 2 * (self.k - 1))
+    }
+}
+
+impl<'a, W: Weight> TrialPlanning for KargerKCut<'a, W> {
+    fn trial_rng(&self) -> StdRng {
+        StdRng::seed_from_u64(self.rng.lock().unwrap().gen())
+    }
+
+    fn single_trial_fail_prob(&self) -> f64 {
+        1f64 - self.single_trial_success_prob()
+    }
+
+    fn single_trial_success_prob(&self) -> f64 {
+        let n = self.graph.num_vertices();
+        let t = self.contracted_vertices();
+        let binomial = (0..t)
+            .map(|i| (n - i) as f64 / (t - i) as f64)
+            .product::<f64>();
+        1f64 / binomial
+    }
+}
+
+impl<'a, W: Weight> KCutAlgo<W> for KargerKCut<'a, W> {
+    fn graph(&self) -> &Graph<W> {
+        self.graph
+    }
+
+    fn k(&self) -> usize {
+        self.k
+    }
+
+    fn trial(&self, rng: &mut StdRng) -> KCutResult<W> {
+        let contraction = self.graph.contract_mapped(self.contracted_vertices(), rng);
+        let groups = best_partition(contraction.graph(), self.k);
+        let groups = contraction
+            .vertex_map()
+            .iter()
+            .map(|&v| groups[v])
+            .collect::<Box<[usize]>>();
+        KCutResult::from_groups(self.graph, &groups)
+    }
+}
+
+// Karger-Stein for k-cuts: contracting down to |V| / 2^(1 / 2(k - 1)) vertices keeps a fixed min
+// k-cut with probability about 1/2, so both halves of the recursion do.
+pub struct KargerSteinKCut<'a, W = usize> {
+    graph: &'a Graph<W>,
+    k: usize,
+    rng: Mutex<StdRng>,
+}
+
+impl<'a, W: Weight> KargerSteinKCut<'a, W> {
+    pub fn new(graph: &'a Graph<W>, k: usize) -> KargerSteinKCut<'a, W> {
+        Self::from_rng(graph, k, StdRng::from_entropy())
+    }
+
+    pub fn with_seed(graph: &'a Graph<W>, k: usize, seed: u64) -> KargerSteinKCut<'a, W> {
+        Self::from_rng(graph, k, StdRng::seed_from_u64(seed))
+    }
+
+    fn from_rng(graph: &'a Graph<W>, k: usize, rng: StdRng) -> KargerSteinKCut<'a, W> {
+        assert!(k >= 2, "a k-cut needs k >= 2 parts, not {k}");
+        Self {
+            graph,
+            k,
+            rng: Mutex::new(rng),
+        }
+    }
+}
+
+impl<'a, W: Weight> TrialPlanning for KargerSteinKCut<'a, W> {
+    fn trial_rng(&self) -> StdRng {
+        StdRng::seed_from_u64(self.rng.lock().unwrap().gen())
+    }
+
+    fn single_trial_fail_prob(&self) -> f64 {
+        let n = self.graph.num_vertices();
+        if n <= 2 * (self.k - 1) {
+            return 0f64;
+        }
+        let branch_height = 2f64 * (self.k - 1) as f64 * (n as f64).log2().ceil();
+        1f64 - 1f64 / (branch_height + 1f64)
+    }
+}
+
+impl<'a, W: Weight> KCutAlgo<W> for KargerSteinKCut<'a, W> {
+    fn graph(&self) -> &Graph<W> {
+        self.graph
+    }
+
+    fn k(&self) -> usize {
+        self.k
+    }
+
+    fn trial(&self, rng: &mut StdRng) -> KCutResult<W> {
+        let n = self.graph.num_vertices();
+        if n <= std::cmp::max(LEAF_VERTICES, 2 * (self.k - 1)) {
+            let karger = KargerKCut::with_seed(self.graph, self.k, rng.gen());
+            karger
+                .iterate_success_lower_bound(1f64 / (n as f64).ln())
+                .expect("the contracted graphs keep at least 2(k - 1) vertices")
+        } else {
+            let shrink = 2f64.powf(1f64 / (2 * (self.k - 1)) as f64);
+            let t = (n as f64 / shrink).ceil() as usize;
+            let t = std::cmp::min(n - 1, std::cmp::max(t, 2 * (self.k - 1)));
+
+            let seeds: [u64; 2] = rng.gen();
+            let branch = |seed| {
+                let mut rng = StdRng::seed_from_u64(seed);
+                let contraction = self.graph.contract_mapped(t, &mut rng);
+                let k_cut = KargerSteinKCut::from_rng(contraction.graph(), self.k, rng)
+                    .iterate()
+                    .expect("the contracted graphs keep at least 2(k - 1) vertices");
+                k_cut.lift(self.graph, contraction.vertex_map())
+            };
+            let (k_cut1, k_cut2) = (branch(seeds[0]), branch(seeds[1]));
+            if k_cut1.value() <= k_cut2.value() {
+                k_cut1
+            } else {
+                k_cut2
+            }
+        }
+    }
+}
+
+// Fails if the graph has fewer than `k` vertices. If it has at least `k` components, returns the
+// k-cut of weight 0 that keeps together the components after the first `k - 1`.
+fn trivial_k_cut<W: Weight, A: KCutAlgo<W> + ?Sized>(
+    algo: &A,
+) -> Result<Option<KCutResult<W>>, MinCutError> {
+    algo.check_graph()?;
+    let components = algo.graph().connected_components();
+    let num_components = components.iter().max().map_or(0, |&c| c + 1);
+    if num_components < algo.k() {
+        return Ok(None);
+    }
+    let groups = components
+        .iter()
+        .map(|&c| std::cmp::min(c, algo.k() - 1))
+        .collect::<Box<[usize]>>();
+    Ok(Some(KCutResult::from_groups(algo.graph(), &groups)))
+}
+
+// The groups of the partition of the vertices into `k` non-empty groups of least crossing weight,
+// found by trying each of them.
+fn best_partition<W: Weight>(graph: &Graph<W>, k: usize) -> Box<[usize]> {
+    fn visit<W: Weight>(
+        graph: &Graph<W>,
+        k: usize,
+        groups: &mut [usize],
+        v: usize,
+        used: usize,
+        best: &mut (W, Box<[usize]>),
+    ) {
+        let n = groups.len();
+        if v == n {
+            let value = graph
+                .adjacency_list()
+                .iter()
+                .flat_map(|node| {
+                    node.edges
+                        .iter()
+                        .filter(|edge| groups[node.vertex] != groups[edge.other])
+                        .map(|edge| edge.weight)
+                })
+                .sum::<W>();
+            if value < best.0 {
+                *best = (value, groups.into());
+            }
+            return;
+        }
+        // Groups are numbered by their first vertex, and every group must get a vertex.
+        let min_group = if n - v == k - used { used } else { 0 };
+        for group in min_group..std::cmp::min(used + 1, k) {
+            groups[v] = group;
+            visit(
+                graph,
+                k,
+                groups,
+                v + 1,
+                std::cmp::max(used, group + 1),
+                best,
+            );
+        }
+    }
+
+    let mut groups = vec![0; graph.num_vertices()];
+    let mut best = (W::MAX, Box::default());
+    visit(graph, k, &mut groups, 0, 0, &mut best);
+    best.1
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data_structures::api::DirectedEdge;
+    use crate::data_structures::graph::Graph;
+    use crate::k_cut::{best_partition, KCutAlgo, KargerKCut, KargerSteinKCut};
+    use crate::min_cut_algo::MinCutAlgo;
+    use crate::stoer_wagner::StoerWagnerAlgo;
+    use crate::traits::EdgeWeightedGraph;
+
+    #[test]
+    fn k_cut_test() {
+        // Three cliques of five vertices joined into a cycle by light edges, and one more between
+        // two of them.
+        let cliques = (0..3).flat_map(|c| {
+            (0..5).flat_map(move |i| (i + 1..5).map(move |j| (5 * c + i, 5 * c + j, 3)))
+        });
+        let edges = cliques
+            .chain([(4, 5, 1), (9, 10, 1), (14, 0, 1), (1, 6, 1)])
+            .flat_map(|(v, w, weight)| [(v, w, weight), (w, v, weight)])
+            .map(DirectedEdge::from);
+        let graph: Graph = Graph::from_directed_edges(15, edges).unwrap();
+        let parts = [[0, 1, 2, 3, 4], [5, 6, 7, 8, 9], [10, 11, 12, 13, 14]];
+
+        let karger = KargerKCut::with_seed(&graph, 3, 1).execute().unwrap();
+        assert_eq!(karger.value(), 8);
+        assert_eq!(karger.parts(), parts.map(|part| part.into()));
+        assert_eq!(karger.crossing_edges().len(), 8);
+
+        let karger_stein = KargerSteinKCut::with_seed(&graph, 3, 1)
+            .iterate_n(5)
+            .unwrap();
+        assert_eq!(karger_stein, karger);
+    }
+
+    #[test]
+    fn best_partition_test() {
+        let edges = [(0, 1, 5), (1, 2, 1), (2, 3, 5), (3, 0, 2)][..]
+            .iter()
+            .flat_map(|&(v, w, weight)| [(v, w, weight), (w, v, weight)])
+            .map(DirectedEdge::from);
+        let graph: Graph = Graph::from_directed_edges(4, edges).unwrap();
+        assert_eq!(best_partition(&graph, 2)[..], [0, 0, 1, 1]);
+        assert_eq!(best_partition(&graph, 3)[..], [0, 0, 1, 2]);
+        assert_eq!(best_partition(&graph, 4)[..], [0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn k_cut_test_files() {
+        let graph = crate::utils::read_graph("src/files/input_random_10_25.txt").unwrap();
+        let min_cut = StoerWagnerAlgo::new(&graph).execute(false).unwrap();
+
+        let two_cut = KargerSteinKCut::with_seed(&graph, 2, 3).execute().unwrap();
+        assert_eq!(two_cut.value(), min_cut.value());
+        assert_eq!(two_cut.parts().len(), 2);
+
+        let three_cut = KargerSteinKCut::with_seed(&graph, 3, 3)
+            .iterate_n(2)
+            .unwrap();
+        assert_eq!(three_cut.parts().len(), 3);
+        assert!(three_cut.value() >= min_cut.value());
+    }
+
+    #[test]
+    fn k_cut_errors() {
+        use crate::min_cut_algo::MinCutError;
+
+        let single: Graph = Graph::from_directed_edges(1, std::iter::empty()).unwrap();
+        assert_eq!(
+            KargerKCut::new(&single, 2).execute(),
+            Err(MinCutError::TooFewVertices { num_vertices: 1 })
+        );
+        let triangle = [(0, 1), (1, 2), (2, 0)].map(DirectedEdge::from);
+        let graph: Graph = Graph::from_undirected_edges(3, triangle.into_iter()).unwrap();
+        let err = MinCutError::TooFewVerticesForKCut {
+            num_vertices: 3,
+            k: 4,
+        };
+        assert_eq!(KargerSteinKCut::new(&graph, 4).iterate(), Err(err));
+        assert_eq!(
+            err.to_string(),
+            "a graph of 3 vertices has no 4-cut, at least 4 are needed"
+        );
+
+        // Two triangles and a lone vertex.
+        let edges = [(0, 2), (2, 4), (4, 0), (1, 3), (3, 5), (5, 1)].map(DirectedEdge::from);
+        let graph: Graph = Graph::from_undirected_edges(7, edges.into_iter()).unwrap();
+        let two_cut = KargerKCut::with_seed(&graph, 2, 1).execute().unwrap();
+        assert_eq!(two_cut.value(), 0);
+        assert_eq!(
+            two_cut.parts(),
+            [[0, 2, 4][..].into(), [1, 3, 5, 6][..].into()]
+        );
+        let three_cut = KargerSteinKCut::with_seed(&graph, 3, 1)
+            .iterate_n(2)
+            .unwrap();
+        assert_eq!(three_cut.value(), 0);
+        assert_eq!(three_cut.parts().len(), 3);
+    }
+}
@@ -1,52 +1,160 @@
-use std::time::Instant;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 
 use crate::data_structures::graph::Graph;
-use crate::min_cut_algo::MinCutAlgo;
+use crate::min_cut_algo::{
+    check_num_vertices, MinCutAlgo, MinCutEnumerator, MinCutError, MinCutResult, TrialPlanning,
+};
+use crate::nagamochi_ibaraki::NagamochiIbarakiAlgo;
 use crate::traits::{EdgeWeightedGraph, MinCutGraph};
+use crate::weight::Weight;
 
-pub struct KargerAlgo<'a> {
-    graph: &'a Graph,
+pub struct KargerAlgo<'a, W = usize> {
+    graph: &'a Graph<W>,
+    rng: Mutex<StdRng>,
 }
 
-impl<'a> KargerAlgo<'a> {
-    pub fn new(graph: &'a Graph) -> KargerAlgo<'a> {
-        Self { graph }
+impl<'a, W: Weight> KargerAlgo<'a, W> {
+    pub fn new(graph: &'a Graph<W>) -> KargerAlgo<'a, W> {
+        Self::from_rng(graph, StdRng::from_entropy())
+    }
+
+    pub fn with_seed(graph: &'a Graph<W>, seed: u64) -> KargerAlgo<'a, W> {
+        Self::from_rng(graph, StdRng::seed_from_u64(seed))
+    }
+
+    fn from_rng(graph: &'a Graph<W>, rng: StdRng) -> KargerAlgo<'a, W> {
+        Self {
+            graph,
+            rng: Mutex::new(rng),
+        }
+    }
+
+    // Lists the distinct cuts of weight at most `alpha` times the min cut found in `num_trials`
+    // trials, sorted by weight. Each trial contracts the graph down to t = min(|V|, ⌈2α⌉) vertices
+    // and lifts every partition of the contracted graph, so it costs 2^(t - 1) cuts: keep α small.
+    //
+    // Fails for a graph of fewer than 2 vertices, and panics if α is below 1 or not finite, or if
+    // t is above 63.
+    pub fn enumerate_approx_min_cuts(
+        &self,
+        alpha: f64,
+        num_trials: usize,
+    ) -> Result<Vec<MinCutResult<W>>, MinCutError> {
+        check_num_vertices(self.graph)?;
+        assert!(
+            alpha >= 1f64 && alpha.is_finite(),
+            "the approximation factor {alpha} is below 1 or not finite"
+        );
+        let n = self.graph.num_vertices();
+        let t = std::cmp::min(n, Self::approx_cut_vertices(alpha));
+        assert!(
+            t <= 63,
+            "contracting to {t} vertices would list 2^{} partitions per trial",
+            t - 1
+        );
+        let max_value = alpha
+            * NagamochiIbarakiAlgo::new(self.graph)
+                .iterate()?
+                .value()
+                .to_f64();
+
+        let mut cuts = BTreeMap::new();
+        let mut rng = self.trial_rng();
+        for _ in 0..num_trials {
+            let contraction = self.graph.contract_mapped(t, &mut rng);
+            // The last vertex stays on side 0 so that every partition is listed once.
+            for mask in 1u64..1 << (t - 1) {
+                let sides = contraction
+                    .vertex_map()
+                    .iter()
+                    .map(|&v| ((mask >> v) & 1) as usize)
+                    .collect::<Box<[usize]>>();
+                let cut = MinCutResult::from_sides(self.graph, &sides);
+                if cut.value().to_f64() <= max_value {
+                    cuts.entry(cut.partition().0.to_vec()).or_insert(cut);
+                }
+            }
+        }
+
+        let mut cuts = cuts.into_values().collect::<Vec<_>>();
+        cuts.sort_by(|a, b| a.value().total_cmp(&b.value()));
+        Ok(cuts)
+    }
+
+    // The number of trials that finds every `alpha`-approximate min cut with probability at least
+    // `prob`. There are at most |V|^⌈2α⌉ of them, each surviving a trial with probability
+    // `approx_cut_survival_prob`.
+    pub fn min_num_approx_cut_trials(&self, alpha: f64, prob: f64) -> usize {
+        let n = self.graph.num_vertices() as f64;
+        let ln_num_cuts = Self::approx_cut_vertices(alpha) as f64 * n.ln();
+        let ln_fail_prob = (-prob).ln_1p() - ln_num_cuts;
+        let survival_prob = self.approx_cut_survival_prob(alpha);
+        let n = (ln_fail_prob / (-survival_prob).ln_1p()).ceil() as usize;
+        std::cmp::max(n, 1)
+    }
+
+    fn approx_cut_vertices(alpha: f64) -> usize {
+        std::cmp::max(2, (2f64 * alpha).ceil() as usize)
+    }
+
+    // A cut of weight at most `alpha` times the min cut survives the contraction from i to i - 1
+    // vertices with probability at least 1 - 2α/i.
+    fn approx_cut_survival_prob(&self, alpha: f64) -> f64 {
+        let t = Self::approx_cut_vertices(alpha);
+        (t + 1..=self.graph.num_vertices())
+            .map(|i| 1f64 - 2f64 * alpha / i as f64)
+            .product()
     }
 }
 
-impl<'a> MinCutAlgo for KargerAlgo<'a> {
-    fn graph(&self) -> &Graph {
-        self.graph
+impl<'a, W: Weight> TrialPlanning for KargerAlgo<'a, W> {
+    fn trial_rng(&self) -> StdRng {
+        StdRng::seed_from_u64(self.rng.lock().unwrap().gen())
     }
 
     fn single_trial_fail_prob(&self) -> f64 {
-        1f64 - 2f64 / (self.graph.num_vertices().pow(2) as f64)
+        1f64 - self.single_trial_success_prob()
     }
 
-    fn iterate(&self) -> usize {
-        let contracted_graph = self.graph.contract_full();
-        contracted_graph.weight()
+    fn single_trial_success_prob(&self) -> f64 {
+        let n = self.graph.num_vertices() as f64;
+        2f64 / (n * n)
+    }
+}
+
+impl<'a, W: Weight> MinCutAlgo<W> for KargerAlgo<'a, W> {
+    fn graph(&self) -> &Graph<W> {
+        self.graph
+    }
+
+    fn trial(&self, rng: &mut StdRng) -> MinCutResult<W> {
+        let contraction = self.graph.contract_mapped(2, rng);
+        let min_cut = MinCutResult::from_sides(self.graph, contraction.vertex_map());
+        // Floating-point weights may round differently once summed in the contracted graph.
+        let (value, contracted) = (
+            min_cut.value().to_f64(),
+            contraction.graph().weight().to_f64(),
+        );
+        debug_assert!((value - contracted).abs() <= 1e-9 * value.max(1f64));
+        min_cut
     }
 
-    fn approx_execute(&self, verbose: bool) -> usize {
+    fn approx_num_trials(&self, last_improvement: Option<usize>) -> usize {
         let step = self.graph.num_vertices();
-        let mut num_trials = step * step;
-        let mut min_cut = usize::MAX;
-        let mut i = 0;
-        let instant = Instant::now();
-        while i < num_trials {
-            i += 1;
-            let new_min_cut = self.iterate();
-            if new_min_cut < min_cut {
-                min_cut = new_min_cut;
-                num_trials = 2 * i + step;
-            }
-        }
-        let elapsed = instant.elapsed();
-        if verbose {
-            self.print_stats(num_trials, min_cut, elapsed);
+        match last_improvement {
+            None => step.saturating_mul(step),
+            Some(i) => i.saturating_mul(2).saturating_add(step),
         }
-        min_cut
+    }
+}
+
+impl<'a, W: Weight> MinCutEnumerator<W> for KargerAlgo<'a, W> {
+    fn trial_min_cuts(&self, rng: &mut StdRng) -> Vec<MinCutResult<W>> {
+        vec![self.trial(rng)]
     }
 }
 
@@ -54,7 +162,7 @@ impl<'a> MinCutAlgo for KargerAlgo<'a> {
 mod tests {
     use crate::data_structures::graph::Graph;
     use crate::karger::KargerAlgo;
-    use crate::min_cut_algo::MinCutAlgo;
+    use crate::min_cut_algo::{MinCutAlgo, MinCutResult, TrialPlanning};
     use crate::traits::EdgeWeightedGraph;
 
     #[test]
@@ -72,11 +180,137 @@ mod tests {
         ][..]
             .iter()
             .map(|it| DirectedEdge::from(*it));
-        let graph: Graph = Graph::from_directed_edges(3, edges);
+        let graph: Graph = Graph::from_directed_edges(3, edges).unwrap();
+
+        let karger = KargerAlgo::new(&graph);
+        karger.iterate_n(9, false).unwrap();
+        karger.iterate_success_lower_bound(0.9, false).unwrap();
+        karger.execute(false).unwrap();
 
+        let min_cut = KargerAlgo::with_seed(&graph, 1).execute(false).unwrap();
+
+        assert_eq!(min_cut.value(), 6);
+        assert_eq!(min_cut.partition(), (&[0][..], &[1, 2][..]));
+        assert_eq!(min_cut.crossing_edges().len(), 3);
+    }
+
+    #[test]
+    fn karger_seeded_test() {
+        let graph = crate::utils::read_graph("src/files/input_random_10_25.txt").unwrap();
+
+        let karger1 = KargerAlgo::with_seed(&graph, 7);
+        let karger2 = KargerAlgo::with_seed(&graph, 7);
+        for _ in 0..10 {
+            assert_eq!(karger1.iterate().unwrap(), karger2.iterate().unwrap());
+        }
+        assert_eq!(
+            karger1.approx_execute(false).unwrap(),
+            karger2.approx_execute(false).unwrap()
+        );
+    }
+
+    #[test]
+    fn karger_parallel_test() {
+        let graph = crate::utils::read_graph("src/files/input_random_10_25.txt").unwrap();
+
+        let karger1 = KargerAlgo::with_seed(&graph, 7);
+        let karger2 = KargerAlgo::with_seed(&graph, 7);
+        assert_eq!(
+            karger1.iterate_n_parallel(50, 4, false).unwrap(),
+            karger2.iterate_n_parallel(50, 4, false).unwrap()
+        );
+        assert_eq!(
+            karger1.approx_execute_parallel(3, false).unwrap(),
+            karger2.approx_execute_parallel(3, false).unwrap()
+        );
+        assert_eq!(karger1.execute_parallel(1, false).unwrap().value(), 12);
+    }
+
+    #[test]
+    fn karger_approx_min_cuts_test() {
+        use crate::data_structures::api::DirectedEdge;
+
+        // A cycle whose min cuts weigh 4, with a chord making some of them weigh 6.
+        let edges = (0..6)
+            .map(|v| (v, (v + 1) % 6, 1))
+            .chain([(0, 3, 1)])
+            .flat_map(|(v, w, weight)| [(v, w, weight), (w, v, weight)])
+            .map(DirectedEdge::from);
+        let graph: Graph = Graph::from_directed_edges(6, edges).unwrap();
+
+        let karger = KargerAlgo::with_seed(&graph, 3);
+        let num_trials = karger.min_num_approx_cut_trials(1.5, 0.99);
+        let cuts = karger.enumerate_approx_min_cuts(1.5, num_trials).unwrap();
+        let values = cuts.iter().map(MinCutResult::value).collect::<Vec<_>>();
+        assert_eq!(values, [vec![4; 6], vec![6; 9]].concat());
+
+        let min_cuts = karger.enumerate_approx_min_cuts(1.0, num_trials).unwrap();
+        assert_eq!(min_cuts.len(), 6);
+    }
+
+    #[test]
+    #[should_panic(expected = "contracting to 64 vertices would list 2^63 partitions per trial")]
+    fn karger_approx_min_cuts_too_many_partitions() {
+        use crate::data_structures::api::DirectedEdge;
+
+        let edges = (0..69).map(|v| DirectedEdge::from((v, v + 1, 1)));
+        let graph: Graph = Graph::from_undirected_edges(70, edges).unwrap();
+        let _ = KargerAlgo::new(&graph).enumerate_approx_min_cuts(32.0, 1);
+    }
+
+    #[test]
+    fn karger_weight_types_test() {
+        use crate::data_structures::api::DirectedEdge;
+        use crate::stoer_wagner::StoerWagnerAlgo;
+
+        // A square with a diagonal, all with fractional capacities.
+        let edges = [
+            (0, 1, 2.5),
+            (1, 2, 0.25),
+            (2, 3, 2.5),
+            (3, 0, 0.5),
+            (0, 2, 0.125),
+        ]
+        .into_iter()
+        .flat_map(|(v, w, weight)| [(v, w, weight), (w, v, weight)])
+        .map(DirectedEdge::from);
+        let graph: Graph<f64> = Graph::from_directed_edges(4, edges).unwrap();
+
+        let min_cut = KargerAlgo::with_seed(&graph, 5).execute(false).unwrap();
+        assert_eq!(min_cut.value(), 1.75);
+        assert_eq!(min_cut.partition(), (&[0, 1][..], &[2, 3][..]));
+        assert_eq!(
+            StoerWagnerAlgo::new(&graph).execute(false).unwrap(),
+            min_cut
+        );
+
+        let graph = crate::utils::read_weighted_graph::<u32>(
+            "src/files/input_random_10_25.txt",
+            Default::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            KargerAlgo::with_seed(&graph, 7)
+                .execute(false)
+                .unwrap()
+                .value(),
+            12
+        );
+    }
+
+    #[test]
+    fn karger_large_graph_trials_test() {
+        let n = 1_000_000;
+        let graph: Graph = Graph::from_directed_edges(n, std::iter::empty()).unwrap();
         let karger = KargerAlgo::new(&graph);
-        karger.iterate_n(9, false);
-        karger.iterate_success_lower_bound(0.9, false);
-        karger.execute(false);
+
+        // About |V|^2 / 2 ln(1 / (1 - prob)) trials, far beyond `i32::MAX`.
+        let num_trials = karger.min_num_trials(0.99);
+        let expected = (n as f64).powi(2) / 2f64 * 100f64.ln();
+        assert!((num_trials as f64 - expected).abs() / expected < 1e-6);
+        assert!(karger.success_lower_bound(num_trials) >= 0.99);
+        assert!(karger.success_lower_bound(num_trials / 2) < 0.99);
+        assert_eq!(karger.approx_num_trials(None), n * n);
+        assert_eq!(karger.approx_num_trials(Some(usize::MAX)), usize::MAX);
     }
 }
@@ -1,68 +1,168 @@
-use std::time::Instant;
+use std::sync::Mutex;
 
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::data_structures::contraction::Contraction;
 use crate::data_structures::graph::Graph;
 use crate::karger::KargerAlgo;
-use crate::min_cut_algo::MinCutAlgo;
+use crate::min_cut_algo::{
+    distinct_min_cuts, MinCutAlgo, MinCutEnumerator, MinCutResult, TrialPlanning,
+};
 use crate::traits::{EdgeWeightedGraph, MinCutGraph};
+use crate::weight::Weight;
 
-pub struct KargerSteinAlgo<'a, const N: usize = 10usize> {
-    graph: &'a Graph,
+pub struct KargerSteinAlgo<'a, const N: usize = 10usize, W = usize> {
+    graph: &'a Graph<W>,
+    rng: Mutex<StdRng>,
+    parallel_depth: usize,
+    parallel_min_vertices: usize,
 }
 
-impl<'a, const N: usize> KargerSteinAlgo<'a, N> {
-    pub fn new(graph: &'a Graph) -> KargerSteinAlgo<'a, N> {
+impl<'a, const N: usize, W: Weight> KargerSteinAlgo<'a, N, W> {
+    pub fn new(graph: &'a Graph<W>) -> KargerSteinAlgo<'a, N, W> {
+        Self::from_rng(graph, StdRng::from_entropy())
+    }
+
+    pub fn with_seed(graph: &'a Graph<W>, seed: u64) -> KargerSteinAlgo<'a, N, W> {
+        Self::from_rng(graph, StdRng::seed_from_u64(seed))
+    }
+
+    pub fn with_parallel_recursion(
+        mut self,
+        max_depth: usize,
+        min_vertices: usize,
+    ) -> KargerSteinAlgo<'a, N, W> {
+        self.parallel_depth = max_depth;
+        self.parallel_min_vertices = min_vertices;
+        self
+    }
+
+    fn from_rng(graph: &'a Graph<W>, rng: StdRng) -> KargerSteinAlgo<'a, N, W> {
         assert!(N >= 2);
-        Self { graph }
+        Self {
+            graph,
+            rng: Mutex::new(rng),
+            parallel_depth: 0,
+            parallel_min_vertices: 0,
+        }
+    }
+
+    fn parallel(&self) -> bool {
+        self.parallel_depth > 0 && self.graph.num_vertices() >= self.parallel_min_vertices
+    }
+
+    fn leaf(&self, rng: &mut StdRng) -> KargerAlgo<'a, W> {
+        KargerAlgo::with_seed(self.graph, rng.gen())
+    }
+
+    fn leaf_success_prob(&self) -> f64 {
+        1f64 / (self.graph.num_vertices() as f64).ln()
+    }
+
+    // A trial returning the min cut of the graph of the leaf it was found in, with the
+    // contraction of the graph down to that graph, so that the cut is lifted only once.
+    fn contracted_trial(&self, rng: &mut StdRng) -> (MinCutResult<W>, Contraction<Graph<W>>) {
+        if self.graph.num_vertices() <= N {
+            let min_cut = self
+                .leaf(rng)
+                .iterate_success_lower_bound(self.leaf_success_prob(), false)
+                .expect("the contracted graphs keep at least 2 vertices");
+            (min_cut, Contraction::identity(self.graph.clone()))
+        } else {
+            let [((min_cut1, next1), contraction1), ((min_cut2, next2), contraction2)] = self
+                .branches(rng, |karger_stein| {
+                    karger_stein.contracted_trial(&mut karger_stein.trial_rng())
+                });
+            if min_cut1.value() <= min_cut2.value() {
+                (min_cut1, contraction1.then(next1))
+            } else {
+                (min_cut2, contraction2.then(next2))
+            }
+        }
+    }
+
+    // Contracts the graph twice independently and recurses on both contracted graphs, returning
+    // the results with the contractions.
+    fn branches<T: Send>(
+        &self,
+        rng: &mut StdRng,
+        recurse: impl Fn(&KargerSteinAlgo<N, W>) -> T + Sync,
+    ) -> [(T, Contraction<Graph<W>>); 2] {
+        let n = self.graph.num_vertices();
+        let t = std::cmp::max(2usize, (n as f64 / 2f64.sqrt()).ceil() as usize);
+        let seeds: [u64; 2] = rng.gen();
+        let branch = |seed| {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let contraction = self.graph.contract_mapped(t, &mut rng);
+            let karger_stein = KargerSteinAlgo::<N, W>::from_rng(contraction.graph(), rng)
+                .with_parallel_recursion(
+                    self.parallel_depth.saturating_sub(1),
+                    self.parallel_min_vertices,
+                );
+            (recurse(&karger_stein), contraction)
+        };
+
+        if self.parallel() {
+            std::thread::scope(|scope| {
+                let handle = scope.spawn(|| branch(seeds[1]));
+                [branch(seeds[0]), handle.join().unwrap()]
+            })
+        } else {
+            [branch(seeds[0]), branch(seeds[1])]
+        }
     }
 }
 
-impl<'a, const N: usize> MinCutAlgo for KargerSteinAlgo<'a, N> {
-    fn graph(&self) -> &Graph {
-        self.graph
+impl<'a, const N: usize, W: Weight> TrialPlanning for KargerSteinAlgo<'a, N, W> {
+    fn trial_rng(&self) -> StdRng {
+        StdRng::seed_from_u64(self.rng.lock().unwrap().gen())
     }
 
     fn single_trial_fail_prob(&self) -> f64 {
         let branch_height = 2f64 * (self.graph.num_vertices() as f64).log2().ceil();
         1f64 - 1f64 / (branch_height + 1f64)
     }
+}
 
-    fn iterate(&self) -> usize {
-        let n = self.graph.num_vertices();
-        if n <= N {
-            let karger = KargerAlgo::new(self.graph);
-            karger
-                .iterate_success_lower_bound(1f64 / (self.graph.num_vertices() as f64).ln(), false)
-        } else {
-            let t = std::cmp::max(2usize, (n as f64 / 2f64.sqrt()).ceil() as usize);
-            let g1 = self.graph.contract(t);
-            let g2 = self.graph.contract(t);
+impl<'a, const N: usize, W: Weight> MinCutAlgo<W> for KargerSteinAlgo<'a, N, W> {
+    fn graph(&self) -> &Graph<W> {
+        self.graph
+    }
 
-            let min_cut1 = KargerSteinAlgo::<N>::new(&g1).iterate();
-            let min_cut2 = KargerSteinAlgo::<N>::new(&g2).iterate();
-            std::cmp::min(min_cut1, min_cut2)
-        }
+    fn trial(&self, rng: &mut StdRng) -> MinCutResult<W> {
+        let (min_cut, contraction) = self.contracted_trial(rng);
+        min_cut.lift(self.graph, contraction.vertex_map())
     }
 
-    fn approx_execute(&self, verbose: bool) -> usize {
+    fn approx_num_trials(&self, last_improvement: Option<usize>) -> usize {
         let step = (self.graph.num_vertices() as f64).ln().ceil() as usize;
-        let mut num_trials = step;
-        let mut min_cut = usize::MAX;
-
-        let mut i = 0;
-        let instant = Instant::now();
-        while i < num_trials {
-            i += 1;
-            let new_min_cut = self.iterate();
-            if new_min_cut < min_cut {
-                min_cut = new_min_cut;
-                num_trials = i + step;
-            }
+        match last_improvement {
+            None => step,
+            Some(i) => i + step,
         }
-        let elapsed = instant.elapsed();
-        if verbose {
-            self.print_stats(num_trials, min_cut, elapsed);
+    }
+}
+
+impl<'a, const N: usize, W: Weight> MinCutEnumerator<W> for KargerSteinAlgo<'a, N, W> {
+    fn trial_min_cuts(&self, rng: &mut StdRng) -> Vec<MinCutResult<W>> {
+        if self.graph.num_vertices() <= N {
+            let leaf = self.leaf(rng);
+            let n = leaf.min_num_trials(self.leaf_success_prob());
+            leaf.enumerate_min_cuts_n(n, false)
+                .expect("the contracted graphs keep at least 2 vertices")
+                .cuts()
+                .to_vec()
+        } else {
+            let branches = self.branches(rng, |karger_stein| {
+                karger_stein.trial_min_cuts(&mut karger_stein.trial_rng())
+            });
+            let cuts = branches.iter().flat_map(|(cuts, contraction)| {
+                cuts.iter()
+                    .map(|cut| cut.lift(self.graph, contraction.vertex_map()))
+            });
+            distinct_min_cuts(cuts.collect())
         }
-        min_cut
     }
 }
 
@@ -70,7 +170,7 @@ impl<'a, const N: usize> MinCutAlgo for KargerSteinAlgo<'a, N> {
 mod tests {
     use crate::data_structures::graph::Graph;
     use crate::karger_stein::KargerSteinAlgo;
-    use crate::min_cut_algo::MinCutAlgo;
+    use crate::min_cut_algo::{MinCutAlgo, MinCutEnumerator};
     use crate::traits::EdgeWeightedGraph;
 
     #[test]
@@ -90,11 +190,90 @@ mod tests {
         ][..]
             .iter()
             .map(|it| DirectedEdge::from(*it));
-        let graph: Graph = Graph::from_directed_edges(4, edges);
+        let graph: Graph = Graph::from_directed_edges(4, edges).unwrap();
 
         let karger_stein = KargerSteinAlgo::<3>::new(&graph);
-        karger_stein.iterate_n(9, false);
-        karger_stein.iterate_success_lower_bound(0.9, false);
-        karger_stein.execute(false);
+        karger_stein.iterate_n(9, false).unwrap();
+        karger_stein
+            .iterate_success_lower_bound(0.9, false)
+            .unwrap();
+        karger_stein.execute(false).unwrap();
+
+        let min_cut = KargerSteinAlgo::<3>::with_seed(&graph, 1)
+            .execute(false)
+            .unwrap();
+        assert_eq!(min_cut.value(), 6);
+        assert_eq!(min_cut.partition(), (&[0][..], &[1, 2, 3][..]));
+    }
+
+    #[test]
+    fn karger_stein_seeded_test() {
+        let graph = crate::utils::read_graph("src/files/input_random_20_75.txt").unwrap();
+
+        let karger_stein1 = KargerSteinAlgo::<10>::with_seed(&graph, 7);
+        let karger_stein2 = KargerSteinAlgo::<10>::with_seed(&graph, 7);
+        for _ in 0..5 {
+            assert_eq!(
+                karger_stein1.iterate().unwrap(),
+                karger_stein2.iterate().unwrap()
+            );
+        }
+        assert_eq!(
+            karger_stein1.approx_execute(false).unwrap(),
+            karger_stein2.approx_execute(false).unwrap()
+        );
+    }
+
+    #[test]
+    fn karger_stein_parallel_recursion_test() {
+        let graph = crate::utils::read_graph("src/files/input_random_20_75.txt").unwrap();
+
+        let sequential = KargerSteinAlgo::<3>::with_seed(&graph, 11);
+        let parallel = KargerSteinAlgo::<3>::with_seed(&graph, 11).with_parallel_recursion(3, 8);
+        for _ in 0..5 {
+            assert_eq!(sequential.iterate().unwrap(), parallel.iterate().unwrap());
+        }
+        assert_eq!(
+            sequential.approx_execute(false).unwrap(),
+            parallel.approx_execute(false).unwrap()
+        );
+    }
+
+    #[test]
+    fn karger_stein_parallel_test() {
+        let graph = crate::utils::read_graph("src/files/input_random_20_75.txt").unwrap();
+
+        let karger_stein1 = KargerSteinAlgo::<10>::with_seed(&graph, 7);
+        let karger_stein2 = KargerSteinAlgo::<10>::with_seed(&graph, 7);
+        assert_eq!(
+            karger_stein1.iterate_n_parallel(10, 4, false).unwrap(),
+            karger_stein2.iterate_n_parallel(10, 4, false).unwrap()
+        );
+        assert_eq!(
+            karger_stein1.approx_execute_parallel(3, false).unwrap(),
+            karger_stein2.approx_execute_parallel(3, false).unwrap()
+        );
+    }
+
+    #[test]
+    fn karger_stein_enumerate_min_cuts_test() {
+        use crate::data_structures::api::DirectedEdge;
+
+        // Every pair of edges of a cycle is a min cut.
+        let edges = (0..8)
+            .flat_map(|v| [(v, (v + 1) % 8, 1), ((v + 1) % 8, v, 1)])
+            .map(DirectedEdge::from);
+        let graph: Graph = Graph::from_directed_edges(8, edges).unwrap();
+
+        let karger_stein = KargerSteinAlgo::<3>::with_seed(&graph, 5);
+        let min_cuts = karger_stein.enumerate_min_cuts(0.99, false).unwrap();
+        assert_eq!(min_cuts.value(), 4);
+        assert_eq!(min_cuts.cuts().len(), 28);
+        assert!(min_cuts.completeness() >= 0.99);
+        assert!(min_cuts.cuts().iter().all(|cut| cut.value() == 4));
+        assert!(min_cuts
+            .cuts()
+            .windows(2)
+            .all(|w| w[0].partition() < w[1].partition()));
     }
 }
@@ -1,9 +1,30 @@
+pub mod cactus;
 mod data_structures;
+pub mod gomory_hu;
+pub mod hao_orlin;
+pub mod k_cut;
 pub mod karger;
 pub mod karger_stein;
-mod min_cut_algo;
-mod traits;
+pub mod max_flow;
+pub mod min_cut_algo;
+pub mod nagamochi_ibaraki;
+pub mod stoer_wagner;
+pub mod traits;
+pub mod tree_packing;
 pub mod utils;
+pub mod weight;
+
+pub use data_structures::api::{DirectedEdge, GraphError};
+pub use data_structures::contraction::Contraction;
+pub use data_structures::graph::Graph;
+pub use data_structures::graph_builder::GraphBuilder;
+pub use min_cut_algo::{MinCutAlgo, TrialPlanning};
+pub use traits::{EdgeWeightedGraph, MinCutGraph};
+
+// Runs the Rust examples of the README as doctests.
+#[cfg(doctest)]
+#[doc = include_str!("../README.md")]
+pub struct ReadmeDoctests;
 
 #[cfg(test)]
 mod tests {
@@ -15,20 +36,23 @@ mod tests {
     use crate::karger::KargerAlgo;
     use crate::karger_stein::KargerSteinAlgo;
     use crate::min_cut_algo::MinCutAlgo;
+    use crate::stoer_wagner::StoerWagnerAlgo;
     use crate::utils::read_graph;
 
     #[test]
     fn karger_sample() {
         let path = "src/files/input_random_40_200.txt";
-        let graph = read_graph(path);
-        KargerAlgo::new(&graph).approx_execute(true);
+        let graph = read_graph(path).unwrap();
+        KargerAlgo::new(&graph).approx_execute(true).unwrap();
     }
 
     #[test]
     fn karger_stein_sample() {
         let path = "src/files/input_random_40_200.txt";
-        let graph = read_graph(path);
-        KargerSteinAlgo::<10>::new(&graph).approx_execute(true);
+        let graph = read_graph(path).unwrap();
+        KargerSteinAlgo::<10>::new(&graph)
+            .approx_execute(true)
+            .unwrap();
     }
 
     fn get_all_test_cases() -> Vec<(PathBuf, Graph, usize)> {
@@ -39,7 +63,8 @@ mod tests {
             .collect::<Box<[PathBuf]>>();
 
         let (mut inputs, mut outputs): (Vec<_>, Vec<_>) = paths
-            .into_iter()
+            .iter()
+            .cloned()
             .partition(|it| it.file_name().unwrap().to_str().unwrap().contains("input"));
         inputs.sort();
         outputs.sort();
@@ -47,7 +72,7 @@ mod tests {
         let expected_min_cut = |path| {
             BufReader::new(File::open(path).unwrap())
                 .lines()
-                .nth(0)
+                .next()
                 .unwrap()
                 .unwrap()
                 .parse::<usize>()
@@ -56,8 +81,8 @@ mod tests {
 
         inputs
             .into_iter()
-            .zip(outputs.into_iter())
-            .map(|(p1, p2)| (p1.clone(), read_graph(p1), expected_min_cut(p2)))
+            .zip(outputs)
+            .map(|(p1, p2)| (p1.clone(), read_graph(p1).unwrap(), expected_min_cut(p2)))
             .collect()
     }
 
@@ -71,10 +96,10 @@ mod tests {
                 file, expected_min_cut
             );
 
-            let actual_min_cut = KargerAlgo::new(&graph).approx_execute(true);
+            let actual_min_cut = KargerAlgo::new(&graph).approx_execute(true).unwrap();
 
             assert_eq!(
-                actual_min_cut,
+                actual_min_cut.value(),
                 expected_min_cut,
                 "Karger algorithm (approx. version) failed for file {:?}. Re-try with stricter error bounds.",
                 file.clone()
@@ -92,14 +117,37 @@ mod tests {
                 file, expected_min_cut
             );
 
-            let actual_min_cut = KargerSteinAlgo::<10>::new(&graph).approx_execute(true);
+            let actual_min_cut = KargerSteinAlgo::<10>::new(&graph)
+                .approx_execute(true)
+                .unwrap();
 
             assert_eq!(
-                actual_min_cut,
+                actual_min_cut.value(),
                 expected_min_cut,
                 "Karger-Stein algorithm (approx. version) failed for file {:?}. Re-try with stricter error bounds.",
                 file.clone()
             );
         }
     }
+
+    #[ignore]
+    #[test]
+    fn stoer_wagner_test_all() {
+        for (file, graph, expected_min_cut) in get_all_test_cases() {
+            println!();
+            println!(
+                "Testing file: {:?} | Expected Min Cut: {}",
+                file, expected_min_cut
+            );
+
+            let actual_min_cut = StoerWagnerAlgo::new(&graph).execute(true).unwrap();
+
+            assert_eq!(
+                actual_min_cut.value(),
+                expected_min_cut,
+                "Stoer-Wagner algorithm failed for file {:?}.",
+                file.clone()
+            );
+        }
+    }
 }
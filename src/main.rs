@@ -0,0 +1,559 @@
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::Instant;
+
+use karger_stein_min_cut::cactus::Cactus;
+use karger_stein_min_cut::gomory_hu::GomoryHuTree;
+use karger_stein_min_cut::hao_orlin::HaoOrlinAlgo;
+use karger_stein_min_cut::k_cut::{KCutAlgo, KCutResult, KargerKCut, KargerSteinKCut};
+use karger_stein_min_cut::karger::KargerAlgo;
+use karger_stein_min_cut::karger_stein::KargerSteinAlgo;
+use karger_stein_min_cut::max_flow::DinicAlgo;
+use karger_stein_min_cut::min_cut_algo::{DirectedMinCutAlgo, MinCutAlgo, MinCutResult};
+use karger_stein_min_cut::nagamochi_ibaraki::{MatulaAlgo, NagamochiIbarakiAlgo};
+use karger_stein_min_cut::stoer_wagner::StoerWagnerAlgo;
+use karger_stein_min_cut::traits::EdgeWeightedGraph;
+use karger_stein_min_cut::tree_packing::TreePackingAlgo;
+use karger_stein_min_cut::utils::{read_graph_with, ReadOptions, Symmetrize};
+
+const USAGE: &str = "\
+Usage:
+    karger-stein-min-cut run <FILE> [OPTIONS]
+    karger-stein-min-cut verify <FILE> [<EXPECTED_FILE>] [OPTIONS]
+    karger-stein-min-cut stats <FILE> [--strict] [--symmetrize <POLICY>]
+    karger-stein-min-cut cactus <FILE> [--seed <SEED>] [--strict] [--symmetrize <POLICY>]
+    karger-stein-min-cut k-cut <FILE> --k <K> [OPTIONS]
+    karger-stein-min-cut gomory-hu <FILE> [--strict] [--symmetrize <POLICY>]
+    karger-stein-min-cut directed <FILE> [--strict] [--symmetrize <POLICY>]
+
+Options:
+    --algo <ALGO>                   karger, karger-stein, stoer-wagner, nagamochi-ibaraki, matula
+                                    or tree-packing (default: karger-stein)
+    --epsilon <E>                   Approximation slack of matula, which finds a (2 + E)-approximate
+                                    min cut (default: 0.5)
+    --trials <N>                    Run exactly N trials
+    --probability <P>               Run enough trials to succeed with probability at least P
+    --seed <SEED>                   Seed of the random number generator (default: random)
+    --threads <N>                   Number of worker threads running trials (default: 1)
+    --k <K>                         Number of parts of `k-cut`, which supports karger and
+                                    karger-stein (default: 2)
+    --format <text|json>            Output format of `run` (default: text)
+    --strict                        Reject headers that don't match the vertices in use
+    --symmetrize <POLICY>           Give the arcs each way between two vertices the same weight:
+                                    sum, max or require-equal (default: keep the arcs as read)
+    --certify                       Check the min cut found by `run` against max-flows
+    -h, --help                      Print this message
+
+Without --trials or --probability, trials are repeated until the min cut stops improving, except
+for `k-cut`, which runs enough trials to succeed with probability 1 - 1/|V|.
+If <EXPECTED_FILE> is omitted, `verify` replaces `input` with `output` in the file name.
+`cactus` prints the cactus of all min cuts in the DOT format.
+`gomory-hu` prints the Gomory-Hu tree of the graph, one `<VERTEX> <PARENT> <WEIGHT>` line per edge.
+`directed` finds the lightest set of arcs leaving some vertices with Hao-Orlin, where the other
+commands take every arc as an undirected edge.";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Algo {
+    Karger,
+    KargerStein,
+    StoerWagner,
+    NagamochiIbaraki,
+    Matula,
+    TreePacking,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Trials {
+    Approx,
+    Exactly(usize),
+    Probability(f64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Format {
+    Text,
+    Json,
+}
+
+#[derive(Debug)]
+struct Options {
+    files: Vec<PathBuf>,
+    algo: Algo,
+    trials: Trials,
+    epsilon: f64,
+    seed: Option<u64>,
+    threads: usize,
+    k: usize,
+    format: Format,
+    strict: bool,
+    symmetrize: Option<Symmetrize>,
+    certify: bool,
+}
+
+impl Options {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+        let mut options = Options {
+            files: Vec::new(),
+            algo: Algo::KargerStein,
+            trials: Trials::Approx,
+            epsilon: 0.5,
+            seed: None,
+            threads: 1,
+            k: 2,
+            format: Format::Text,
+            strict: false,
+            symmetrize: None,
+            certify: false,
+        };
+
+        while let Some(arg) = args.next() {
+            let mut value = |name: &str| {
+                args.next()
+                    .ok_or_else(|| format!("missing value for {name}"))
+            };
+            match arg.as_str() {
+                "--algo" => {
+                    options.algo = match value("--algo")?.as_str() {
+                        "karger" => Algo::Karger,
+                        "karger-stein" => Algo::KargerStein,
+                        "stoer-wagner" => Algo::StoerWagner,
+                        "nagamochi-ibaraki" => Algo::NagamochiIbaraki,
+                        "matula" => Algo::Matula,
+                        "tree-packing" => Algo::TreePacking,
+                        other => return Err(format!("unknown algorithm {other:?}")),
+                    }
+                }
+                "--trials" => {
+                    let trials = value("--trials")?;
+                    match trials.parse::<usize>() {
+                        Ok(n) if n > 0 => options.trials = Trials::Exactly(n),
+                        _ => return Err(format!("invalid number of trials {trials:?}")),
+                    }
+                }
+                "--probability" => {
+                    let prob = value("--probability")?;
+                    match prob.parse::<f64>() {
+                        Ok(p) if p > 0f64 && p < 1f64 => options.trials = Trials::Probability(p),
+                        _ => return Err(format!("invalid probability {prob:?}")),
+                    }
+                }
+                "--epsilon" => {
+                    let epsilon = value("--epsilon")?;
+                    match epsilon.parse::<f64>() {
+                        Ok(e) if e > 0f64 => options.epsilon = e,
+                        _ => return Err(format!("invalid epsilon {epsilon:?}")),
+                    }
+                }
+                "--seed" => {
+                    let seed = value("--seed")?;
+                    match seed.parse::<u64>() {
+                        Ok(seed) => options.seed = Some(seed),
+                        _ => return Err(format!("invalid seed {seed:?}")),
+                    }
+                }
+                "--threads" => {
+                    let threads = value("--threads")?;
+                    match threads.parse::<usize>() {
+                        Ok(n) if n > 0 => options.threads = n,
+                        _ => return Err(format!("invalid number of threads {threads:?}")),
+                    }
+                }
+                "--k" => {
+                    let k = value("--k")?;
+                    match k.parse::<usize>() {
+                        Ok(k) if k >= 2 => options.k = k,
+                        _ => return Err(format!("invalid number of parts {k:?}")),
+                    }
+                }
+                "--format" => {
+                    options.format = match value("--format")?.as_str() {
+                        "text" => Format::Text,
+                        "json" => Format::Json,
+                        other => return Err(format!("unknown format {other:?}")),
+                    }
+                }
+                "--strict" => options.strict = true,
+                "--symmetrize" => {
+                    options.symmetrize = match value("--symmetrize")?.as_str() {
+                        "sum" => Some(Symmetrize::Sum),
+                        "max" => Some(Symmetrize::Max),
+                        "require-equal" => Some(Symmetrize::RequireEqual),
+                        other => return Err(format!("unknown symmetrization {other:?}")),
+                    }
+                }
+                "--certify" => options.certify = true,
+                flag if flag.starts_with('-') => return Err(format!("unknown option {flag:?}")),
+                file => options.files.push(PathBuf::from(file)),
+            }
+        }
+        Ok(options)
+    }
+
+    fn seed(&self) -> u64 {
+        self.seed.unwrap_or_else(rand::random)
+    }
+
+    fn read_options(&self) -> ReadOptions {
+        ReadOptions {
+            strict: self.strict,
+            symmetrize: self.symmetrize,
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let mut args = std::env::args().skip(1);
+    let command = args.next();
+    if matches!(command.as_deref(), None | Some("-h") | Some("--help")) {
+        println!("{USAGE}");
+        return ExitCode::SUCCESS;
+    }
+
+    let result = Options::parse(args).and_then(|options| match command.as_deref() {
+        Some("run") => run(&options),
+        Some("verify") => verify(&options),
+        Some("stats") => stats(&options),
+        Some("cactus") => cactus(&options),
+        Some("k-cut") => k_cut(&options),
+        Some("gomory-hu") => gomory_hu(&options),
+        Some("directed") => directed(&options),
+        Some(other) => Err(format!("unknown command {other:?}")),
+        None => unreachable!(),
+    });
+
+    match result {
+        Ok(code) => code,
+        Err(err) => {
+            eprintln!("error: {err}");
+            eprintln!("Run with --help for usage.");
+            ExitCode::from(2)
+        }
+    }
+}
+
+fn run(options: &Options) -> Result<ExitCode, String> {
+    let [file] = &options.files[..] else {
+        return Err("`run` expects exactly one input file".to_string());
+    };
+    let graph =
+        read_graph_with(file, options.read_options()).map_err(|err| format!("{file:?}: {err}"))?;
+
+    let verbose = options.format == Format::Text;
+    let seed = options.seed();
+    if verbose {
+        println!("Seed: {seed}");
+    }
+    let min_cut = match options.algo {
+        Algo::Karger => execute(&KargerAlgo::with_seed(&graph, seed), options, verbose),
+        Algo::KargerStein => execute(
+            &KargerSteinAlgo::<10>::with_seed(&graph, seed),
+            options,
+            verbose,
+        ),
+        Algo::StoerWagner => execute(&StoerWagnerAlgo::new(&graph), options, verbose),
+        Algo::NagamochiIbaraki => execute(&NagamochiIbarakiAlgo::new(&graph), options, verbose),
+        Algo::Matula => execute(&MatulaAlgo::new(&graph, options.epsilon), options, verbose),
+        Algo::TreePacking => execute(&TreePackingAlgo::with_seed(&graph, seed), options, verbose),
+    }
+    .map_err(|err| format!("{file:?}: {err}"))?;
+
+    let certified = options
+        .certify
+        .then(|| DinicAlgo::new(&graph).certify(&min_cut));
+    match options.format {
+        Format::Text => {
+            println!(
+                "Partition: {:?} | {:?}",
+                min_cut.partition().0,
+                min_cut.partition().1
+            );
+            println!("Crossing edges: {:?}", min_cut.crossing_edges());
+            if let Some(certified) = certified {
+                println!("Certified: {certified}");
+            }
+        }
+        Format::Json => println!("{}", to_json(&min_cut, certified)),
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn verify(options: &Options) -> Result<ExitCode, String> {
+    let (input, expected) = match &options.files[..] {
+        [input] => (input, expected_output_path(input)?),
+        [input, expected] => (input, expected.clone()),
+        _ => {
+            return Err(
+                "`verify` expects an input file and an optional expected output file".to_string(),
+            )
+        }
+    };
+    let graph = read_graph_with(input, options.read_options())
+        .map_err(|err| format!("{input:?}: {err}"))?;
+    let expected_min_cut = std::fs::read_to_string(&expected)
+        .map_err(|err| format!("{expected:?}: {err}"))?
+        .split_whitespace()
+        .next()
+        .and_then(|token| token.parse::<usize>().ok())
+        .ok_or_else(|| format!("{expected:?}: expected a min cut value"))?;
+
+    let seed = options.seed();
+    println!("Seed: {seed}");
+    let min_cut = match options.algo {
+        Algo::Karger => execute(&KargerAlgo::with_seed(&graph, seed), options, true),
+        Algo::KargerStein => execute(
+            &KargerSteinAlgo::<10>::with_seed(&graph, seed),
+            options,
+            true,
+        ),
+        Algo::StoerWagner => execute(&StoerWagnerAlgo::new(&graph), options, true),
+        Algo::NagamochiIbaraki => execute(&NagamochiIbarakiAlgo::new(&graph), options, true),
+        Algo::Matula => execute(&MatulaAlgo::new(&graph, options.epsilon), options, true),
+        Algo::TreePacking => execute(&TreePackingAlgo::with_seed(&graph, seed), options, true),
+    }
+    .map_err(|err| format!("{input:?}: {err}"))?;
+
+    if min_cut.value() == expected_min_cut {
+        println!("OK: {input:?} | Expected Min Cut: {expected_min_cut}");
+        Ok(ExitCode::SUCCESS)
+    } else {
+        println!(
+            "FAILED: {input:?} | Expected Min Cut: {expected_min_cut} | Actual Min Cut: {}",
+            min_cut.value()
+        );
+        Ok(ExitCode::FAILURE)
+    }
+}
+
+fn stats(options: &Options) -> Result<ExitCode, String> {
+    let [file] = &options.files[..] else {
+        return Err("`stats` expects exactly one input file".to_string());
+    };
+    let instant = Instant::now();
+    let graph =
+        read_graph_with(file, options.read_options()).map_err(|err| format!("{file:?}: {err}"))?;
+    println!(
+        "|V|: {} | |E|: {} | Total weight: {} | Asymmetric arcs: {} | Elapsed time: {:?}",
+        graph.num_vertices(),
+        graph.num_edges(),
+        graph.weight(),
+        graph.symmetry_violations().len(),
+        instant.elapsed()
+    );
+    Ok(ExitCode::SUCCESS)
+}
+
+fn cactus(options: &Options) -> Result<ExitCode, String> {
+    let [file] = &options.files[..] else {
+        return Err("`cactus` expects exactly one input file".to_string());
+    };
+    let graph =
+        read_graph_with(file, options.read_options()).map_err(|err| format!("{file:?}: {err}"))?;
+    let cactus =
+        Cactus::with_seed(&graph, options.seed()).map_err(|err| format!("{file:?}: {err}"))?;
+    print!("{}", cactus.to_dot());
+    Ok(ExitCode::SUCCESS)
+}
+
+fn gomory_hu(options: &Options) -> Result<ExitCode, String> {
+    let [file] = &options.files[..] else {
+        return Err("`gomory-hu` expects exactly one input file".to_string());
+    };
+    let graph =
+        read_graph_with(file, options.read_options()).map_err(|err| format!("{file:?}: {err}"))?;
+    print!("{}", GomoryHuTree::new(&graph));
+    Ok(ExitCode::SUCCESS)
+}
+
+fn directed(options: &Options) -> Result<ExitCode, String> {
+    let [file] = &options.files[..] else {
+        return Err("`directed` expects exactly one input file".to_string());
+    };
+    let graph =
+        read_graph_with(file, options.read_options()).map_err(|err| format!("{file:?}: {err}"))?;
+    let instant = Instant::now();
+    let min_cut = HaoOrlinAlgo::new(&graph)
+        .directed_min_cut()
+        .map_err(|err| format!("{file:?}: {err}"))?;
+    println!(
+        "Min cut: {} | Elapsed time: {:?}",
+        min_cut.value(),
+        instant.elapsed()
+    );
+    println!(
+        "Partition: {:?} | {:?}",
+        min_cut.partition().0,
+        min_cut.partition().1
+    );
+    println!("Crossing arcs: {:?}", min_cut.crossing_arcs());
+    Ok(ExitCode::SUCCESS)
+}
+
+fn k_cut(options: &Options) -> Result<ExitCode, String> {
+    let [file] = &options.files[..] else {
+        return Err("`k-cut` expects exactly one input file".to_string());
+    };
+    let graph =
+        read_graph_with(file, options.read_options()).map_err(|err| format!("{file:?}: {err}"))?;
+    let seed = options.seed();
+    println!("Seed: {seed}");
+    let instant = Instant::now();
+    let k_cut = match options.algo {
+        Algo::Karger => execute_k_cut(&KargerKCut::with_seed(&graph, options.k, seed), options),
+        Algo::KargerStein => execute_k_cut(
+            &KargerSteinKCut::with_seed(&graph, options.k, seed),
+            options,
+        ),
+        _ => return Err("`k-cut` supports karger and karger-stein only".to_string()),
+    }?;
+    println!(
+        "K-Cut: {} | K: {} | |V|: {} | Elapsed time: {:?}",
+        k_cut.value(),
+        options.k,
+        graph.num_vertices(),
+        instant.elapsed()
+    );
+    for part in k_cut.parts() {
+        println!("Part: {part:?}");
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn execute_k_cut(algo: &dyn KCutAlgo, options: &Options) -> Result<KCutResult, String> {
+    match options.trials {
+        Trials::Approx => algo.execute(),
+        Trials::Exactly(n) => algo.iterate_n(n),
+        Trials::Probability(p) => algo.iterate_success_lower_bound(p),
+    }
+    .map_err(|err| err.to_string())
+}
+
+fn execute(
+    algo: &(dyn MinCutAlgo + Sync),
+    options: &Options,
+    verbose: bool,
+) -> Result<MinCutResult, String> {
+    match (options.trials, options.threads) {
+        (Trials::Approx, 1) => algo.approx_execute(verbose),
+        (Trials::Approx, threads) => algo.approx_execute_parallel(threads, verbose),
+        (Trials::Exactly(n), 1) => algo.iterate_n(n, verbose),
+        (Trials::Exactly(n), threads) => algo.iterate_n_parallel(n, threads, verbose),
+        (Trials::Probability(p), 1) => algo.iterate_success_lower_bound(p, verbose),
+        (Trials::Probability(p), threads) => {
+            algo.iterate_success_lower_bound_parallel(p, threads, verbose)
+        }
+    }
+    .map_err(|err| err.to_string())
+}
+
+fn expected_output_path(input: &Path) -> Result<PathBuf, String> {
+    let file_name = input
+        .file_name()
+        .and_then(|name| name.to_str())
+        .filter(|name| name.contains("input"))
+        .ok_or_else(|| format!("{input:?}: cannot derive the expected output file name"))?;
+    Ok(input.with_file_name(file_name.replacen("input", "output", 1)))
+}
+
+fn to_json(min_cut: &MinCutResult, certified: Option<bool>) -> String {
+    let list = |vertices: &[usize]| {
+        vertices
+            .iter()
+            .map(usize::to_string)
+            .collect::<Vec<_>>()
+            .join(",")
+    };
+    let mut json = String::new();
+    write!(
+        json,
+        "{{\"min_cut\":{},\"partition\":[[{}],[{}]],\"crossing_edges\":[",
+        min_cut.value(),
+        list(min_cut.partition().0),
+        list(min_cut.partition().1)
+    )
+    .unwrap();
+    for (i, (v, w, weight)) in min_cut.crossing_edges().iter().enumerate() {
+        if i > 0 {
+            json.push(',');
+        }
+        write!(json, "[{v},{w},{weight}]").unwrap();
+    }
+    json.push(']');
+    if let Some(certified) = certified {
+        write!(json, ",\"certified\":{certified}").unwrap();
+    }
+    json.push('}');
+    json
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use karger_stein_min_cut::utils::Symmetrize;
+
+    use crate::{expected_output_path, Algo, Format, Options, Trials};
+
+    fn parse(args: &[&str]) -> Result<Options, String> {
+        Options::parse(args.iter().map(|it| it.to_string()))
+    }
+
+    #[test]
+    fn parse_options() {
+        let options = parse(&[
+            "a.txt", "--algo", "karger", "--trials", "5", "--format", "json",
+        ])
+        .unwrap();
+        assert_eq!(options.files, [PathBuf::from("a.txt")]);
+        assert_eq!(options.algo, Algo::Karger);
+        assert_eq!(options.trials, Trials::Exactly(5));
+        assert_eq!(options.format, Format::Json);
+        assert!(!options.strict);
+
+        let options = parse(&["a.txt", "--seed", "42"]).unwrap();
+        assert_eq!(options.seed, Some(42));
+        assert_eq!(options.seed(), 42);
+        assert_eq!(options.threads, 1);
+
+        let options = parse(&["a.txt", "--threads", "8"]).unwrap();
+        assert_eq!(options.threads, 8);
+
+        let options = parse(&["--probability", "0.99", "--strict", "a.txt"]).unwrap();
+        assert_eq!(options.algo, Algo::KargerStein);
+        assert_eq!(options.seed, None);
+        assert_eq!(options.trials, Trials::Probability(0.99));
+        assert!(options.strict);
+        assert!(!options.certify);
+        assert!(parse(&["--certify"]).unwrap().certify);
+
+        assert_eq!(
+            parse(&["--algo", "stoer-wagner"]).unwrap().algo,
+            Algo::StoerWagner
+        );
+        let options = parse(&["--algo", "matula", "--epsilon", "0.1"]).unwrap();
+        assert_eq!(options.algo, Algo::Matula);
+        assert_eq!(options.epsilon, 0.1);
+        assert_eq!(parse(&["--k", "3"]).unwrap().k, 3);
+        assert_eq!(options.symmetrize, None);
+        assert_eq!(
+            parse(&["--symmetrize", "max"]).unwrap().symmetrize,
+            Some(Symmetrize::Max)
+        );
+        assert!(parse(&["--symmetrize", "min"]).is_err());
+        assert!(parse(&["--algo", "foo"]).is_err());
+        assert!(parse(&["--epsilon", "0"]).is_err());
+        assert!(parse(&["--trials", "0"]).is_err());
+        assert!(parse(&["--probability", "1.5"]).is_err());
+        assert!(parse(&["--trials"]).is_err());
+        assert!(parse(&["--seed", "-1"]).is_err());
+        assert!(parse(&["--threads", "0"]).is_err());
+        assert!(parse(&["--k", "1"]).is_err());
+        assert!(parse(&["--unknown"]).is_err());
+
+        assert_eq!(
+            expected_output_path(Path::new("src/files/input_random_1_6.txt")).unwrap(),
+            PathBuf::from("src/files/output_random_1_6.txt")
+        );
+        assert!(expected_output_path(Path::new("graph.txt")).is_err());
+    }
+}
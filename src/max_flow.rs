@@ -0,0 +1,306 @@
+use std::collections::VecDeque;
+
+use crate::data_structures::api::DirectedEdge;
+use crate::data_structures::graph::Graph;
+use crate::min_cut_algo::{
+    check_num_vertices, DirectedMinCutAlgo, DirectedMinCutResult, MinCutError, MinCutResult,
+};
+use crate::traits::EdgeWeightedGraph;
+use crate::weight::Weight;
+
+#[derive(Debug)]
+pub struct MaxFlowResult<W = usize> {
+    value: W,
+    partition: (Box<[usize]>, Box<[usize]>),
+    residual: Graph<W>,
+}
+
+impl<W: Weight> MaxFlowResult<W> {
+    pub fn value(&self) -> W {
+        self.value
+    }
+
+    /// The vertices reachable from the source in the residual graph, and the others.
+    pub fn partition(&self) -> (&[usize], &[usize]) {
+        (&self.partition.0, &self.partition.1)
+    }
+
+    /// The arcs with capacity left, including the reverse arcs of the flow.
+    pub fn residual(&self) -> &Graph<W> {
+        &self.residual
+    }
+}
+
+/// Dinic's max-flow along the arcs of a graph, in O(|V|^2 |E|).
+pub struct DinicAlgo<'a, W = usize> {
+    graph: &'a Graph<W>,
+}
+
+impl<'a, W: Weight> DinicAlgo<'a, W> {
+    pub fn new(graph: &'a Graph<W>) -> DinicAlgo<'a, W> {
+        Self { graph }
+    }
+
+    pub fn max_flow(&self, s: usize, t: usize) -> MaxFlowResult<W> {
+        let n = self.graph.num_vertices();
+        assert!(s < n && t < n && s != t);
+        let mut network = FlowNetwork::new(self.graph);
+        let mut value = W::ZERO;
+        while network.build_levels(s, t) {
+            value += network.blocking_flow(s, t);
+        }
+
+        network.build_levels(s, t);
+        let (source_side, sink_side): (Vec<_>, Vec<_>) =
+            (0..n).partition(|&v| network.level[v] != usize::MAX);
+        let residual_arcs = (0..network.to.len())
+            .filter(|&e| network.capacity[e] > W::ZERO)
+            .map(|e| DirectedEdge::Weighted(network.to[e ^ 1], network.to[e], network.capacity[e]));
+        // Each arc and its reverse keep the capacity they had together, and so the total weight.
+        MaxFlowResult {
+            value,
+            partition: (source_side.into_boxed_slice(), sink_side.into_boxed_slice()),
+            residual: Graph::from_checked_edges(n, residual_arcs)
+                .expect("the residual graph weighs as much as the graph"),
+        }
+    }
+
+    /// The global min cut as the least of the min cuts between vertex 0 and every other vertex,
+    /// with the capacity between two vertices being the weight of the arcs between them in either
+    /// direction. Fails for a graph of fewer than 2 vertices.
+    pub fn global_min_cut(&self) -> Result<MinCutResult<W>, MinCutError> {
+        check_num_vertices(self.graph)?;
+        let n = self.graph.num_vertices();
+        let symmetrized = self.graph.symmetrized();
+        let dinic = DinicAlgo::new(&symmetrized);
+        let max_flow = (1..n)
+            .map(|t| dinic.max_flow(0, t))
+            .min_by(|a, b| a.value().total_cmp(&b.value()))
+            .unwrap();
+
+        let mut sides = vec![1; n];
+        for &v in max_flow.partition().0 {
+            sides[v] = 0;
+        }
+        Ok(MinCutResult::from_sides(self.graph, &sides))
+    }
+
+    /// Whether `min_cut` is a min cut of the graph, independently of the algorithm that found it.
+    pub fn certify(&self, min_cut: &MinCutResult<W>) -> bool {
+        let (first, second) = min_cut.partition();
+        let mut sides = vec![0; first.len() + second.len()];
+        for &v in second {
+            sides[v] = 1;
+        }
+        let recomputed = MinCutResult::from_sides(self.graph, &sides);
+        !second.is_empty()
+            && recomputed.value() == min_cut.value()
+            && self
+                .global_min_cut()
+                .is_ok_and(|global| global.value() == min_cut.value())
+    }
+}
+
+impl<W: Weight> DirectedMinCutAlgo<W> for DinicAlgo<'_, W> {
+    fn graph(&self) -> &Graph<W> {
+        self.graph
+    }
+
+    // The least of the min cuts from vertex 0 to every other vertex and back, in 2(|V| - 1)
+    // max-flows.
+    fn directed_min_cut(&self) -> Result<DirectedMinCutResult<W>, MinCutError> {
+        check_num_vertices(self.graph)?;
+        let n = self.graph.num_vertices();
+        let reversed = self.graph.reversed();
+        let backward = DinicAlgo::new(&reversed);
+        let (max_flow, leaves_source) = (1..n)
+            .flat_map(|t| {
+                [
+                    (self.max_flow(0, t), true),
+                    (backward.max_flow(0, t), false),
+                ]
+            })
+            .min_by(|(a, _), (b, _)| a.value().total_cmp(&b.value()))
+            .unwrap();
+
+        let mut source_side = vec![!leaves_source; n];
+        for &v in max_flow.partition().0 {
+            source_side[v] = leaves_source;
+        }
+        Ok(DirectedMinCutResult::from_source_side(
+            self.graph,
+            &source_side,
+        ))
+    }
+}
+
+// Arcs are stored in pairs, the reverse of arc `e` being `e ^ 1`.
+struct FlowNetwork<W> {
+    to: Vec<usize>,
+    capacity: Vec<W>,
+    arcs: Box<[Vec<usize>]>,
+    level: Box<[usize]>,
+    next_arc: Box<[usize]>,
+}
+
+impl<W: Weight> FlowNetwork<W> {
+    fn new(graph: &Graph<W>) -> Self {
+        let n = graph.num_vertices();
+        let mut network = FlowNetwork {
+            to: Vec::with_capacity(2 * graph.num_edges()),
+            capacity: Vec::with_capacity(2 * graph.num_edges()),
+            arcs: vec![Vec::new(); n].into_boxed_slice(),
+            level: vec![usize::MAX; n].into_boxed_slice(),
+            next_arc: vec![0; n].into_boxed_slice(),
+        };
+        for node in graph.adjacency_list() {
+            for edge in node.edges.iter() {
+                network.arcs[node.vertex].push(network.to.len());
+                network.to.push(edge.other);
+                network.capacity.push(edge.weight);
+                network.arcs[edge.other].push(network.to.len());
+                network.to.push(node.vertex);
+                network.capacity.push(W::ZERO);
+            }
+        }
+        network
+    }
+
+    // Levels vertices by their distance from `s` in the residual graph, and tells whether `t` is
+    // reachable.
+    fn build_levels(&mut self, s: usize, t: usize) -> bool {
+        self.level.fill(usize::MAX);
+        self.next_arc.fill(0);
+        self.level[s] = 0;
+        let mut queue = VecDeque::from([s]);
+        while let Some(v) = queue.pop_front() {
+            for &e in self.arcs[v].iter() {
+                let w = self.to[e];
+                if self.capacity[e] > W::ZERO && self.level[w] == usize::MAX {
+                    self.level[w] = self.level[v] + 1;
+                    queue.push_back(w);
+                }
+            }
+        }
+        self.level[t] != usize::MAX
+    }
+
+    // Saturates every shortest augmenting path, walking the level graph without recursion.
+    fn blocking_flow(&mut self, s: usize, t: usize) -> W {
+        let mut total = W::ZERO;
+        let mut path = Vec::new();
+        let mut v = s;
+        loop {
+            if v == t {
+                let flow = path
+                    .iter()
+                    .map(|&e| self.capacity[e])
+                    .min_by(W::total_cmp)
+                    .unwrap();
+                for &e in path.iter() {
+                    self.capacity[e] -= flow;
+                    self.capacity[e ^ 1] += flow;
+                }
+                total += flow;
+                let saturated = path
+                    .iter()
+                    .position(|&e| self.capacity[e] == W::ZERO)
+                    .unwrap();
+                v = self.to[path[saturated] ^ 1];
+                path.truncate(saturated);
+                continue;
+            }
+
+            let arcs = &self.arcs[v];
+            while self.next_arc[v] < arcs.len() {
+                let e = arcs[self.next_arc[v]];
+                let w = self.to[e];
+                if self.capacity[e] > W::ZERO && self.level[w] == self.level[v] + 1 {
+                    break;
+                }
+                self.next_arc[v] += 1;
+            }
+            if self.next_arc[v] < arcs.len() {
+                let e = arcs[self.next_arc[v]];
+                path.push(e);
+                v = self.to[e];
+            } else if let Some(e) = path.pop() {
+                // A dead end, which no later path of this phase goes through.
+                self.level[v] = usize::MAX;
+                v = self.to[e ^ 1];
+                self.next_arc[v] += 1;
+            } else {
+                return total;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data_structures::api::DirectedEdge;
+    use crate::data_structures::graph::Graph;
+    use crate::karger_stein::KargerSteinAlgo;
+    use crate::max_flow::DinicAlgo;
+    use crate::min_cut_algo::MinCutAlgo;
+    use crate::stoer_wagner::StoerWagnerAlgo;
+    use crate::traits::EdgeWeightedGraph;
+
+    #[test]
+    fn max_flow_test() {
+        let edges = [
+            (0, 1, 10),
+            (0, 2, 10),
+            (1, 2, 2),
+            (1, 3, 4),
+            (1, 4, 8),
+            (2, 4, 9),
+            (3, 5, 10),
+            (4, 3, 6),
+            (4, 5, 10),
+        ][..]
+            .iter()
+            .map(|it| DirectedEdge::from(*it));
+        let graph: Graph = Graph::from_directed_edges(6, edges).unwrap();
+
+        let max_flow = DinicAlgo::new(&graph).max_flow(0, 5);
+        assert_eq!(max_flow.value(), 19);
+        assert_eq!(max_flow.partition(), (&[0, 2][..], &[1, 3, 4, 5][..]));
+        let residual = max_flow.residual();
+        assert_eq!(residual.num_vertices(), 6);
+        assert_eq!(residual.adjacency_list()[0].weight, 20 - max_flow.value());
+        assert_eq!(residual.adjacency_list()[5].weight, max_flow.value());
+
+        let max_flow = DinicAlgo::new(&graph).max_flow(5, 0);
+        assert_eq!(max_flow.value(), 0);
+        assert_eq!(max_flow.partition(), (&[5][..], &[0, 1, 2, 3, 4][..]));
+    }
+
+    #[test]
+    fn max_flow_certify_test_files() {
+        for input in [
+            "src/files/input_random_1_6.txt",
+            "src/files/input_random_10_25.txt",
+            "src/files/input_random_20_75.txt",
+        ] {
+            let graph = crate::utils::read_graph(input).unwrap();
+            let dinic = DinicAlgo::new(&graph);
+            let min_cut = StoerWagnerAlgo::new(&graph).execute(false).unwrap();
+            assert_eq!(
+                dinic.global_min_cut().unwrap().value(),
+                min_cut.value(),
+                "{input}"
+            );
+            assert!(dinic.certify(&min_cut), "{input}");
+
+            let karger_stein = KargerSteinAlgo::<10>::with_seed(&graph, 1)
+                .iterate_n(1, false)
+                .unwrap();
+            assert_eq!(
+                dinic.certify(&karger_stein),
+                karger_stein.value() == min_cut.value(),
+                "{input}"
+            );
+        }
+    }
+}
@@ -1,60 +1,764 @@
+use std::fmt::{Display, Formatter};
+use std::sync::mpsc;
 use std::time::{Duration, Instant};
 
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
 use crate::data_structures::graph::Graph;
 use crate::traits::EdgeWeightedGraph;
+use crate::weight::Weight;
+
+/// A partition of the vertices into parts with the edges between them, the parts ordered by their
+/// smallest vertex.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KCutResult<W = usize> {
+    value: W,
+    parts: Box<[Box<[usize]>]>,
+    crossing_edges: Box<[(usize, usize, W)]>,
+}
+
+impl<W: Weight> KCutResult<W> {
+    pub(crate) fn from_groups(graph: &Graph<W>, groups: &[usize]) -> Self {
+        let mut part_of_group = vec![usize::MAX; groups.len()];
+        let mut parts = Vec::<Vec<usize>>::new();
+        for (v, &group) in groups.iter().enumerate() {
+            if part_of_group[group] == usize::MAX {
+                part_of_group[group] = parts.len();
+                parts.push(Vec::new());
+            }
+            parts[part_of_group[group]].push(v);
+        }
+
+        let crossing_edges = graph
+            .edges()
+            .filter(|&(v, w, _)| groups[v] != groups[w])
+            .collect::<Box<[(usize, usize, W)]>>();
+
+        KCutResult {
+            value: crossing_edges.iter().map(|edge| edge.2).sum(),
+            parts: parts.into_iter().map(Vec::into_boxed_slice).collect(),
+            crossing_edges,
+        }
+    }
+
+    // The same parts in the graph a contraction with the given vertex map was taken from.
+    pub(crate) fn lift(&self, graph: &Graph<W>, vertex_map: &[usize]) -> Self {
+        let mut groups = vec![0; vertex_map.len()];
+        for (group, part) in self.parts.iter().enumerate() {
+            for &v in part.iter() {
+                groups[v] = group;
+            }
+        }
+        let groups = vertex_map
+            .iter()
+            .map(|&v| groups[v])
+            .collect::<Box<[usize]>>();
+        KCutResult::from_groups(graph, &groups)
+    }
+
+    pub fn value(&self) -> W {
+        self.value
+    }
+
+    pub fn parts(&self) -> &[Box<[usize]>] {
+        &self.parts
+    }
+
+    pub fn crossing_edges(&self) -> &[(usize, usize, W)] {
+        &self.crossing_edges
+    }
+}
+
+/// A cut into two parts, the first one holding vertex 0.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MinCutResult<W = usize>(KCutResult<W>);
+
+impl<W: Weight> MinCutResult<W> {
+    // The first side holds the vertices on the side of vertex 0, the second one all the others.
+    pub(crate) fn from_sides(graph: &Graph<W>, sides: &[usize]) -> Self {
+        let groups = sides
+            .iter()
+            .map(|&side| (side != sides[0]) as usize)
+            .collect::<Box<[usize]>>();
+        MinCutResult(KCutResult::from_groups(graph, &groups))
+    }
+
+    pub(crate) fn lift(&self, graph: &Graph<W>, vertex_map: &[usize]) -> Self {
+        MinCutResult(self.0.lift(graph, vertex_map))
+    }
+
+    pub fn value(&self) -> W {
+        self.0.value()
+    }
+
+    pub fn partition(&self) -> (&[usize], &[usize]) {
+        match self.0.parts() {
+            [first, second] => (first, second),
+            // Every vertex on the same side.
+            parts => (&parts[0], &[]),
+        }
+    }
+
+    pub fn crossing_edges(&self) -> &[(usize, usize, W)] {
+        self.0.crossing_edges()
+    }
+}
+
+/// A min cut of a directed graph: the lightest set of arcs leaving a non-empty proper subset of the
+/// vertices, which comes first in the partition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirectedMinCutResult<W = usize> {
+    value: W,
+    partition: (Box<[usize]>, Box<[usize]>),
+    crossing_arcs: Box<[(usize, usize, W)]>,
+}
+
+impl<W: Weight> DirectedMinCutResult<W> {
+    pub(crate) fn from_source_side(graph: &Graph<W>, source_side: &[bool]) -> Self {
+        let (first, second): (Vec<_>, Vec<_>) =
+            (0..source_side.len()).partition(|&v| source_side[v]);
+
+        let crossing_arcs = graph
+            .adjacency_list()
+            .iter()
+            .filter(|node| source_side[node.vertex])
+            .flat_map(|node| {
+                node.edges
+                    .iter()
+                    .filter(|edge| !source_side[edge.other])
+                    .map(|edge| (node.vertex, edge.other, edge.weight))
+            })
+            .collect::<Box<[(usize, usize, W)]>>();
+
+        DirectedMinCutResult {
+            value: crossing_arcs.iter().map(|arc| arc.2).sum(),
+            partition: (first.into_boxed_slice(), second.into_boxed_slice()),
+            crossing_arcs,
+        }
+    }
+
+    pub fn value(&self) -> W {
+        self.value
+    }
+
+    /// The vertices the crossing arcs leave, and the ones they enter.
+    pub fn partition(&self) -> (&[usize], &[usize]) {
+        (&self.partition.0, &self.partition.1)
+    }
 
-pub trait MinCutAlgo {
-    fn graph(&self) -> &Graph;
+    pub fn crossing_arcs(&self) -> &[(usize, usize, W)] {
+        &self.crossing_arcs
+    }
+}
+
+/// The distinct min cuts found across a number of trials. `completeness` is a lower bound on the
+/// probability that no min cut is missing.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MinCutEnumeration<W = usize> {
+    value: W,
+    cuts: Box<[MinCutResult<W>]>,
+    num_trials: usize,
+    completeness: f64,
+}
+
+impl<W: Weight> MinCutEnumeration<W> {
+    pub fn value(&self) -> W {
+        self.value
+    }
+
+    pub fn cuts(&self) -> &[MinCutResult<W>] {
+        &self.cuts
+    }
+
+    pub fn num_trials(&self) -> usize {
+        self.num_trials
+    }
+
+    pub fn completeness(&self) -> f64 {
+        self.completeness
+    }
+}
+
+/// Why a graph has no min cut to find, or no cactus to build.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MinCutError {
+    /// A cut needs a vertex on either side.
+    TooFewVertices { num_vertices: usize },
+    /// A k-cut needs a vertex in each of its `k` parts.
+    TooFewVerticesForKCut { num_vertices: usize, k: usize },
+    /// The min cut weighs 0, as in a disconnected graph, so the min cuts have no cactus.
+    ZeroMinCut,
+    /// The min cuts listed do not fit a cactus, as when the enumeration missed some of them.
+    IncompleteMinCuts,
+}
 
+impl Display for MinCutError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MinCutError::TooFewVertices { num_vertices } => write!(
+                f,
+                "a graph of {num_vertices} vertices has no cut, at least 2 are needed"
+            ),
+            MinCutError::TooFewVerticesForKCut { num_vertices, k } => write!(
+                f,
+                "a graph of {num_vertices} vertices has no {k}-cut, at least {k} are needed"
+            ),
+            MinCutError::ZeroMinCut => write!(
+                f,
+                "the min cut weighs 0, as in a disconnected graph, and the min cuts have no cactus"
+            ),
+            MinCutError::IncompleteMinCuts => write!(
+                f,
+                "the min cuts listed do not fit a cactus, some of them are likely missing"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for MinCutError {}
+
+/// The number of independent trials a randomized algorithm runs to succeed with a given
+/// probability, shared by the min cut and the k-cut algorithms.
+pub trait TrialPlanning {
     fn single_trial_fail_prob(&self) -> f64;
 
-    fn iterate(&self) -> usize;
+    /// Algorithms whose trials rarely succeed override this, as `1 - single_trial_fail_prob()`
+    /// rounds to 0 below about 1e-16.
+    fn single_trial_success_prob(&self) -> f64 {
+        1f64 - self.single_trial_fail_prob()
+    }
 
-    fn approx_execute(&self, verbose: bool) -> usize;
+    /// The generator for the trials of one run. Seeded algorithms draw it from their seed so that
+    /// their runs repeat, and deterministic ones ignore it.
+    fn trial_rng(&self) -> StdRng {
+        StdRng::from_entropy()
+    }
 
     fn success_lower_bound(&self, n: usize) -> f64 {
-        let n_trial_fail_prob = self.single_trial_fail_prob().powi(n as i32);
-        1f64 - n_trial_fail_prob
+        -(n as f64 * ln_single_trial_fail_prob(self)).exp_m1()
     }
 
     fn min_num_trials(&self, prob: f64) -> usize {
-        let fail_prob = 1f64 - prob;
-        (fail_prob.log2() / self.single_trial_fail_prob().log2()).ceil() as usize
+        let n = ((-prob).ln_1p() / ln_single_trial_fail_prob(self)).ceil() as usize;
+        std::cmp::max(n, 1)
+    }
+}
+
+/// The sides of a min cut of the graph taken as undirected, an arc from `v` to `w` weighing on the
+/// edge between them as much as an arc from `w` to `v`.
+pub trait MinCutAlgo<W: Weight = usize>: TrialPlanning {
+    fn graph(&self) -> &Graph<W>;
+
+    fn trial(&self, rng: &mut StdRng) -> MinCutResult<W>;
+
+    fn approx_num_trials(&self, last_improvement: Option<usize>) -> usize;
+
+    /// Every entry point fails with this error on a graph without cuts, and returns a cut of weight
+    /// 0 around the component of vertex 0 on a disconnected graph without running any trial.
+    fn check_graph(&self) -> Result<(), MinCutError> {
+        check_num_vertices(self.graph())
     }
 
-    fn execute(&self, verbose: bool) -> usize {
+    fn iterate(&self) -> Result<MinCutResult<W>, MinCutError> {
+        if let Some((min_cut, _)) = trivial_min_cut(self, false)? {
+            return Ok(min_cut);
+        }
+        Ok(self.trial(&mut self.trial_rng()))
+    }
+
+    fn execute(&self, verbose: bool) -> Result<MinCutResult<W>, MinCutError> {
         let expected_lower_bound = 1f64 - 1f64 / self.graph().num_vertices() as f64;
         self.iterate_success_lower_bound(expected_lower_bound, verbose)
     }
 
-    fn iterate_success_lower_bound(&self, prob: f64, verbose: bool) -> usize {
+    fn execute_parallel(
+        &self,
+        threads: usize,
+        verbose: bool,
+    ) -> Result<MinCutResult<W>, MinCutError>
+    where
+        Self: Sync,
+    {
+        let expected_lower_bound = 1f64 - 1f64 / self.graph().num_vertices() as f64;
+        self.iterate_success_lower_bound_parallel(expected_lower_bound, threads, verbose)
+    }
+
+    fn iterate_success_lower_bound(
+        &self,
+        prob: f64,
+        verbose: bool,
+    ) -> Result<MinCutResult<W>, MinCutError> {
+        if let Some((min_cut, _)) = trivial_min_cut(self, verbose)? {
+            return Ok(min_cut);
+        }
         let n = self.min_num_trials(prob);
         let instant = Instant::now();
-        let min_cut = self.iterate_n(n, false);
+        let min_cut = self.iterate_n(n, false)?;
         let elapsed = instant.elapsed();
         if verbose {
-            self.print_stats(n, min_cut, elapsed);
+            self.print_stats(n, min_cut.value(), elapsed);
+        }
+        Ok(min_cut)
+    }
+
+    fn iterate_success_lower_bound_parallel(
+        &self,
+        prob: f64,
+        threads: usize,
+        verbose: bool,
+    ) -> Result<MinCutResult<W>, MinCutError>
+    where
+        Self: Sync,
+    {
+        if let Some((min_cut, _)) = trivial_min_cut(self, verbose)? {
+            return Ok(min_cut);
         }
-        min_cut
+        let n = self.min_num_trials(prob);
+        self.iterate_n_parallel(n, threads, verbose)
     }
 
-    fn iterate_n(&self, n: usize, verbose: bool) -> usize {
-        let mut min_cut = usize::MAX;
+    fn iterate_n(&self, n: usize, verbose: bool) -> Result<MinCutResult<W>, MinCutError> {
+        if let Some((min_cut, _)) = trivial_min_cut(self, verbose)? {
+            return Ok(min_cut);
+        }
         let instant = Instant::now();
-        for _ in 0..n {
-            min_cut = std::cmp::min(min_cut, self.iterate())
+        let mut rng = self.trial_rng();
+        let mut min_cut = self.trial(&mut rng);
+        for _ in 1..n {
+            let new_min_cut = self.trial(&mut rng);
+            if new_min_cut.value() < min_cut.value() {
+                min_cut = new_min_cut;
+            }
         }
         let elapsed = instant.elapsed();
         if verbose {
-            self.print_stats(n, min_cut, elapsed);
+            self.print_stats(n, min_cut.value(), elapsed);
         }
-        min_cut
+        Ok(min_cut)
     }
 
-    fn print_stats(&self, num_trials: usize, min_cut: usize, duration: Duration) {
+    fn iterate_n_parallel(
+        &self,
+        n: usize,
+        threads: usize,
+        verbose: bool,
+    ) -> Result<MinCutResult<W>, MinCutError>
+    where
+        Self: Sync,
+    {
+        if let Some((min_cut, _)) = trivial_min_cut(self, verbose)? {
+            return Ok(min_cut);
+        }
+        let instant = Instant::now();
+        let min_cut = with_workers(self, threads, |next_trials| {
+            let mut min_cut = next_trials(1).remove(0);
+            let mut i = 1;
+            while i < n {
+                for new_min_cut in next_trials(n - i) {
+                    i += 1;
+                    if new_min_cut.value() < min_cut.value() {
+                        min_cut = new_min_cut;
+                    }
+                }
+            }
+            min_cut
+        });
+        let elapsed = instant.elapsed();
+        if verbose {
+            self.print_stats(n, min_cut.value(), elapsed);
+        }
+        Ok(min_cut)
+    }
+
+    fn approx_execute(&self, verbose: bool) -> Result<MinCutResult<W>, MinCutError> {
+        if let Some((min_cut, _)) = trivial_min_cut(self, verbose)? {
+            return Ok(min_cut);
+        }
+        let instant = Instant::now();
+        let mut rng = self.trial_rng();
+        let (num_trials, min_cut) = approx_search(self, |_| vec![self.trial(&mut rng)]);
+        let elapsed = instant.elapsed();
+        if verbose {
+            self.print_stats(num_trials, min_cut.value(), elapsed);
+        }
+        Ok(min_cut)
+    }
+
+    fn approx_execute_parallel(
+        &self,
+        threads: usize,
+        verbose: bool,
+    ) -> Result<MinCutResult<W>, MinCutError>
+    where
+        Self: Sync,
+    {
+        if let Some((min_cut, _)) = trivial_min_cut(self, verbose)? {
+            return Ok(min_cut);
+        }
+        let instant = Instant::now();
+        let (num_trials, min_cut) = with_workers(self, threads, |next_trials| {
+            approx_search(self, next_trials)
+        });
+        let elapsed = instant.elapsed();
+        if verbose {
+            self.print_stats(num_trials, min_cut.value(), elapsed);
+        }
+        Ok(min_cut)
+    }
+
+    fn print_stats(&self, num_trials: usize, min_cut: W, duration: Duration) {
         let success_prob = self.success_lower_bound(num_trials) * 100f64;
         println!("Min Cut: {} | |V|: {} | |E|: {} | Number of trials: {} | Probability of success: {:.2}% | Elapsed time: {:?}",
                  min_cut, self.graph().num_vertices(), self.graph().num_edges(), num_trials, success_prob, duration
         );
     }
 }
+
+/// Algorithms whose single trial returns every fixed min cut with probability at least `1 -
+/// single_trial_fail_prob()`. As a graph has at most |V|(|V| - 1)/2 min cuts, a union bound over
+/// them estimates how complete the enumeration is.
+pub trait MinCutEnumerator<W: Weight = usize>: MinCutAlgo<W> {
+    fn trial_min_cuts(&self, rng: &mut StdRng) -> Vec<MinCutResult<W>>;
+
+    fn enumeration_completeness(&self, n: usize) -> f64 {
+        let n_trial_fail_prob = (n as f64 * ln_single_trial_fail_prob(self)).exp();
+        f64::max(
+            0f64,
+            1f64 - max_num_min_cuts(self.graph()) * n_trial_fail_prob,
+        )
+    }
+
+    fn min_num_enumeration_trials(&self, prob: f64) -> usize {
+        let ln_fail_prob = (-prob).ln_1p() - max_num_min_cuts(self.graph()).ln();
+        let n = (ln_fail_prob / ln_single_trial_fail_prob(self)).ceil() as usize;
+        std::cmp::max(n, 1)
+    }
+
+    fn enumerate_min_cuts(
+        &self,
+        prob: f64,
+        verbose: bool,
+    ) -> Result<MinCutEnumeration<W>, MinCutError> {
+        let n = self.min_num_enumeration_trials(prob);
+        self.enumerate_min_cuts_n(n, verbose)
+    }
+
+    /// A disconnected graph of c components has 2^(c - 1) - 1 min cuts, of which only the one
+    /// around the component of vertex 0 is listed.
+    fn enumerate_min_cuts_n(
+        &self,
+        n: usize,
+        verbose: bool,
+    ) -> Result<MinCutEnumeration<W>, MinCutError> {
+        if let Some((min_cut, num_components)) = trivial_min_cut(self, verbose)? {
+            return Ok(MinCutEnumeration {
+                value: min_cut.value(),
+                cuts: Box::new([min_cut]),
+                num_trials: 0,
+                completeness: if num_components == 2 { 1f64 } else { 0f64 },
+            });
+        }
+        let n = std::cmp::max(n, 1);
+        let instant = Instant::now();
+        let mut cuts = Vec::new();
+        let mut rng = self.trial_rng();
+        for _ in 0..n {
+            cuts.extend(self.trial_min_cuts(&mut rng));
+            cuts = distinct_min_cuts(cuts);
+        }
+        let elapsed = instant.elapsed();
+
+        let enumeration = MinCutEnumeration {
+            value: cuts[0].value(),
+            cuts: cuts.into_boxed_slice(),
+            num_trials: n,
+            completeness: self.enumeration_completeness(n),
+        };
+        if verbose {
+            self.print_stats(enumeration.num_trials, enumeration.value, elapsed);
+            println!(
+                "Distinct min cuts: {} | Probability that none is missing: {:.2}%",
+                enumeration.cuts.len(),
+                enumeration.completeness * 100f64
+            );
+        }
+        Ok(enumeration)
+    }
+}
+
+/// Exact algorithms for the min cut of the graph as given, where only the arcs leaving one side
+/// count.
+pub trait DirectedMinCutAlgo<W: Weight = usize> {
+    fn graph(&self) -> &Graph<W>;
+
+    /// Fails for a graph of fewer than 2 vertices.
+    fn directed_min_cut(&self) -> Result<DirectedMinCutResult<W>, MinCutError>;
+}
+
+pub(crate) fn check_num_vertices<W: Weight>(graph: &Graph<W>) -> Result<(), MinCutError> {
+    let num_vertices = graph.num_vertices();
+    if num_vertices < 2 {
+        return Err(MinCutError::TooFewVertices { num_vertices });
+    }
+    Ok(())
+}
+
+// Fails if the graph has no cut. If it is disconnected, returns the cut around the component of
+// vertex 0 with the number of components.
+fn trivial_min_cut<W: Weight, A: MinCutAlgo<W> + ?Sized>(
+    algo: &A,
+    verbose: bool,
+) -> Result<Option<(MinCutResult<W>, usize)>, MinCutError> {
+    algo.check_graph()?;
+    let graph = algo.graph();
+    let components = graph.connected_components();
+    let num_components = components.iter().max().map_or(0, |&c| c + 1);
+    if num_components == 1 {
+        return Ok(None);
+    }
+
+    let sides = components
+        .iter()
+        .map(|&c| (c != 0) as usize)
+        .collect::<Box<[usize]>>();
+    let min_cut = MinCutResult::from_sides(graph, &sides);
+    if verbose {
+        println!(
+            "Min Cut: {} | |V|: {} | |E|: {} | Connected components: {}",
+            min_cut.value(),
+            graph.num_vertices(),
+            graph.num_edges(),
+            num_components
+        );
+    }
+    Ok(Some((min_cut, num_components)))
+}
+
+// Precise even when a single trial almost surely fails, as Karger's do on large graphs.
+fn ln_single_trial_fail_prob<A: TrialPlanning + ?Sized>(algo: &A) -> f64 {
+    (-algo.single_trial_success_prob()).ln_1p()
+}
+
+fn max_num_min_cuts<W: Weight>(graph: &Graph<W>) -> f64 {
+    let n = graph.num_vertices() as f64;
+    f64::max(1f64, n * (n - 1f64) / 2f64)
+}
+
+// Keeps the cuts of minimum value, once per partition. Partitions are canonical as their first set
+// always holds vertex 0. Floating-point values count as minimum up to the rounding of their sums.
+pub(crate) fn distinct_min_cuts<W: Weight>(mut cuts: Vec<MinCutResult<W>>) -> Vec<MinCutResult<W>> {
+    let min_value = cuts.iter().map(MinCutResult::value).min_by(W::total_cmp);
+    cuts.retain(|cut| min_value.is_some_and(|min_value| cut.value().approx_eq(min_value)));
+    cuts.sort_by(|a, b| a.partition().0.cmp(b.partition().0));
+    cuts.dedup_by(|a, b| a.partition().0 == b.partition().0);
+    cuts
+}
+
+fn approx_search<W: Weight, A: MinCutAlgo<W> + ?Sized>(
+    algo: &A,
+    mut next_trials: impl FnMut(usize) -> Vec<MinCutResult<W>>,
+) -> (usize, MinCutResult<W>) {
+    let mut num_trials = algo.approx_num_trials(None);
+    let mut min_cut: Option<MinCutResult<W>> = None;
+    let mut i = 0;
+    while i < num_trials {
+        for new_min_cut in next_trials(num_trials - i) {
+            if i >= num_trials {
+                break;
+            }
+            i += 1;
+            let improved = match &min_cut {
+                Some(min_cut) => new_min_cut.value() < min_cut.value(),
+                None => true,
+            };
+            if improved {
+                min_cut = Some(new_min_cut);
+                num_trials = algo.approx_num_trials(Some(i));
+            }
+        }
+    }
+    (num_trials, min_cut.expect("at least one trial is run"))
+}
+
+fn with_workers<W: Weight, A: MinCutAlgo<W> + Sync + ?Sized, T>(
+    algo: &A,
+    threads: usize,
+    f: impl FnOnce(&mut dyn FnMut(usize) -> Vec<MinCutResult<W>>) -> T,
+) -> T {
+    assert!(threads > 0);
+    let seeds = {
+        let mut rng = algo.trial_rng();
+        (0..threads).map(|_| rng.gen()).collect::<Vec<u64>>()
+    };
+
+    std::thread::scope(|scope| {
+        let workers = seeds
+            .into_iter()
+            .map(|seed| {
+                let (job_sender, job_receiver) = mpsc::channel::<()>();
+                let (result_sender, result_receiver) = mpsc::channel();
+                scope.spawn(move || {
+                    let mut rng = StdRng::seed_from_u64(seed);
+                    for () in job_receiver {
+                        if result_sender.send(algo.trial(&mut rng)).is_err() {
+                            break;
+                        }
+                    }
+                });
+                (job_sender, result_receiver)
+            })
+            .collect::<Vec<_>>();
+
+        let mut next_trials = |n: usize| {
+            let workers = &workers[..std::cmp::min(n, workers.len())];
+            for (job_sender, _) in workers {
+                job_sender.send(()).unwrap();
+            }
+            workers
+                .iter()
+                .map(|(_, result_receiver)| result_receiver.recv().unwrap())
+                .collect()
+        };
+        f(&mut next_trials)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data_structures::api::DirectedEdge;
+    use crate::data_structures::graph::Graph;
+    use crate::min_cut_algo::{distinct_min_cuts, MinCutResult};
+    use crate::traits::EdgeWeightedGraph;
+
+    #[test]
+    fn min_cut_result_from_sides() {
+        let edges = [
+            (0, 1, 4),
+            (1, 0, 4),
+            (1, 2, 1),
+            (2, 1, 1),
+            (2, 3, 5),
+            (3, 2, 5),
+        ][..]
+            .iter()
+            .map(|it| DirectedEdge::from(*it));
+        let graph: Graph = Graph::from_directed_edges(4, edges).unwrap();
+
+        let result = MinCutResult::from_sides(&graph, &[1, 1, 0, 0]);
+        assert_eq!(result.value(), 2);
+        assert_eq!(result.partition(), (&[0, 1][..], &[2, 3][..]));
+        let mut crossing_edges = result.crossing_edges().to_vec();
+        crossing_edges.sort();
+        assert_eq!(crossing_edges, [(1, 2, 1), (2, 1, 1)]);
+
+        let edges = [(0, 1, 1), (1, 0, 1)][..]
+            .iter()
+            .map(|it| DirectedEdge::from(*it));
+        let contracted: Graph = Graph::from_directed_edges(2, edges).unwrap();
+        let lifted = MinCutResult::from_sides(&contracted, &[1, 0]).lift(&graph, &[0, 0, 1, 1]);
+        assert_eq!(lifted, result);
+    }
+
+    #[test]
+    fn distinct_float_min_cuts() {
+        // A path whose two cuts weigh 0.3, summed in different orders.
+        let edges = [(0, 1, 0.1 + 0.2), (1, 2, 0.3)]
+            .into_iter()
+            .flat_map(|(v, w, weight)| [(v, w, weight), (w, v, weight)])
+            .map(DirectedEdge::from);
+        let graph: Graph<f64> = Graph::from_directed_edges(3, edges).unwrap();
+        let cuts = [[0, 1, 1], [0, 0, 1], [0, 1, 0], [0, 0, 1]]
+            .map(|sides| MinCutResult::from_sides(&graph, &sides))
+            .to_vec();
+
+        let min_cuts = distinct_min_cuts(cuts);
+        assert_eq!(min_cuts.len(), 2);
+        assert_eq!(min_cuts[0].partition(), (&[0][..], &[1, 2][..]));
+        assert_eq!(min_cuts[1].partition(), (&[0, 1][..], &[2][..]));
+    }
+
+    #[test]
+    fn disconnected_graphs() {
+        use crate::karger::KargerAlgo;
+        use crate::karger_stein::KargerSteinAlgo;
+        use crate::min_cut_algo::{MinCutAlgo, MinCutEnumerator, MinCutError};
+        use crate::nagamochi_ibaraki::NagamochiIbarakiAlgo;
+        use crate::stoer_wagner::StoerWagnerAlgo;
+        use crate::tree_packing::TreePackingAlgo;
+
+        // Two triangles, the second one holding vertex 1.
+        let edges = [(0, 2), (2, 4), (4, 0), (1, 3), (3, 5), (5, 1)]
+            .into_iter()
+            .flat_map(|(v, w)| [(v, w, 1), (w, v, 1)])
+            .map(DirectedEdge::from);
+        let graph: Graph = Graph::from_directed_edges(6, edges).unwrap();
+
+        let algos: [&(dyn MinCutAlgo + Sync); 5] = [
+            &KargerAlgo::with_seed(&graph, 1),
+            &KargerSteinAlgo::<10>::with_seed(&graph, 1),
+            &StoerWagnerAlgo::new(&graph),
+            &NagamochiIbarakiAlgo::new(&graph),
+            &TreePackingAlgo::with_seed(&graph, 1),
+        ];
+        for algo in algos {
+            assert_eq!(algo.check_graph(), Ok(()));
+            for min_cut in [
+                algo.execute(false).unwrap(),
+                algo.execute_parallel(2, false).unwrap(),
+                algo.iterate().unwrap(),
+                algo.iterate_n(3, false).unwrap(),
+                algo.iterate_n_parallel(3, 2, false).unwrap(),
+                algo.approx_execute(false).unwrap(),
+                algo.approx_execute_parallel(2, false).unwrap(),
+            ] {
+                assert_eq!(min_cut.value(), 0);
+                assert_eq!(min_cut.partition(), (&[0, 2, 4][..], &[1, 3, 5][..]));
+            }
+        }
+
+        let min_cuts = KargerSteinAlgo::<10>::with_seed(&graph, 1)
+            .enumerate_min_cuts(0.9, false)
+            .unwrap();
+        assert_eq!(min_cuts.value(), 0);
+        assert_eq!(min_cuts.cuts().len(), 1);
+        assert_eq!(min_cuts.completeness(), 1f64);
+
+        let edgeless: Graph = Graph::from_directed_edges(3, std::iter::empty()).unwrap();
+        let min_cuts = KargerAlgo::with_seed(&edgeless, 1)
+            .enumerate_min_cuts(0.9, false)
+            .unwrap();
+        assert_eq!(min_cuts.cuts()[0].partition(), (&[0][..], &[1, 2][..]));
+        assert_eq!(min_cuts.completeness(), 0f64);
+
+        let single: Graph = Graph::from_directed_edges(1, std::iter::empty()).unwrap();
+        let karger = KargerAlgo::new(&single);
+        assert_eq!(
+            karger.check_graph(),
+            Err(MinCutError::TooFewVertices { num_vertices: 1 })
+        );
+    }
+
+    #[test]
+    fn single_vertex_graph() {
+        use crate::karger_stein::KargerSteinAlgo;
+        use crate::min_cut_algo::{MinCutAlgo, MinCutEnumerator, MinCutError};
+        use crate::stoer_wagner::StoerWagnerAlgo;
+
+        let single: Graph = Graph::from_directed_edges(1, std::iter::empty()).unwrap();
+        let err = MinCutError::TooFewVertices { num_vertices: 1 };
+        let stoer_wagner = StoerWagnerAlgo::new(&single);
+        assert_eq!(stoer_wagner.execute(false), Err(err));
+        assert_eq!(stoer_wagner.execute_parallel(2, false), Err(err));
+        assert_eq!(stoer_wagner.iterate(), Err(err));
+        assert_eq!(stoer_wagner.iterate_n(3, false), Err(err));
+        assert_eq!(stoer_wagner.approx_execute(false), Err(err));
+        assert_eq!(stoer_wagner.approx_execute_parallel(2, false), Err(err));
+        let karger_stein = KargerSteinAlgo::<10>::new(&single);
+        assert_eq!(karger_stein.enumerate_min_cuts(0.9, false).err(), Some(err));
+        assert_eq!(
+            err.to_string(),
+            "a graph of 1 vertices has no cut, at least 2 are needed"
+        );
+    }
+}
@@ -0,0 +1,207 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use rand::rngs::StdRng;
+
+use crate::data_structures::contraction::Contraction;
+use crate::data_structures::graph::Graph;
+use crate::data_structures::union_find::UnionFind;
+use crate::min_cut_algo::{MinCutAlgo, MinCutResult, TrialPlanning};
+use crate::traits::EdgeWeightedGraph;
+use crate::weight::{OrdWeight, Weight};
+
+pub struct NagamochiIbarakiAlgo<'a, W = usize> {
+    graph: &'a Graph<W>,
+}
+
+impl<'a, W: Weight> NagamochiIbarakiAlgo<'a, W> {
+    pub fn new(graph: &'a Graph<W>) -> NagamochiIbarakiAlgo<'a, W> {
+        Self { graph }
+    }
+}
+
+impl<'a, W: Weight> TrialPlanning for NagamochiIbarakiAlgo<'a, W> {
+    fn single_trial_fail_prob(&self) -> f64 {
+        0f64
+    }
+}
+
+impl<'a, W: Weight> MinCutAlgo<W> for NagamochiIbarakiAlgo<'a, W> {
+    fn graph(&self) -> &Graph<W> {
+        self.graph
+    }
+
+    fn trial(&self, _rng: &mut StdRng) -> MinCutResult<W> {
+        scan_and_contract(self.graph, |q, min_cut, _| q >= min_cut)
+    }
+
+    fn approx_num_trials(&self, _last_improvement: Option<usize>) -> usize {
+        1
+    }
+}
+
+/// Matula's (2 + ε)-approximation: the returned cut is at most (2 + ε) times the min cut.
+pub struct MatulaAlgo<'a, W = usize> {
+    graph: &'a Graph<W>,
+    epsilon: f64,
+}
+
+impl<'a, W: Weight> MatulaAlgo<'a, W> {
+    pub fn new(graph: &'a Graph<W>, epsilon: f64) -> MatulaAlgo<'a, W> {
+        assert!(epsilon > 0f64);
+        Self { graph, epsilon }
+    }
+}
+
+impl<'a, W: Weight> TrialPlanning for MatulaAlgo<'a, W> {
+    fn single_trial_fail_prob(&self) -> f64 {
+        0f64
+    }
+}
+
+impl<'a, W: Weight> MinCutAlgo<W> for MatulaAlgo<'a, W> {
+    fn graph(&self) -> &Graph<W> {
+        self.graph
+    }
+
+    fn trial(&self, _rng: &mut StdRng) -> MinCutResult<W> {
+        let factor = 2f64 + self.epsilon;
+        scan_and_contract(self.graph, |q, _, min_degree| {
+            q.to_f64() * factor >= min_degree.to_f64()
+        })
+    }
+
+    fn approx_num_trials(&self, _last_improvement: Option<usize>) -> usize {
+        1
+    }
+}
+
+// Repeatedly records the minimum weighted degree as a candidate cut, then contracts every edge
+// deemed contractible from the maximum-adjacency scan value `q` of the edge, the best cut found so
+// far and the current minimum degree.
+fn scan_and_contract<W: Weight>(
+    graph: &Graph<W>,
+    contractible: impl Fn(W, W, W) -> bool,
+) -> MinCutResult<W> {
+    let mut contraction = Contraction::identity(graph.symmetrized());
+    let mut min_cut = W::MAX;
+    let mut sides = vec![0; graph.num_vertices()];
+
+    while contraction.graph().num_vertices() > 1 {
+        let current = contraction.graph();
+        let vertex = current.min_degree_vertex().unwrap();
+        let min_degree = current.weighted_degree(vertex);
+        if min_degree < min_cut {
+            min_cut = min_degree;
+            for (side, &v) in sides.iter_mut().zip(contraction.vertex_map()) {
+                *side = (v == vertex) as usize;
+            }
+        }
+        if min_cut == W::ZERO {
+            break;
+        }
+
+        let mut union_find = UnionFind::new(current.num_vertices());
+        maximum_adjacency_scan(current, |v, w, q| {
+            if contractible(q, min_cut, min_degree) {
+                union_find.union(v, w);
+            }
+        });
+
+        let next = current.condense(union_find);
+        contraction = contraction.then(next);
+    }
+
+    MinCutResult::from_sides(graph, &sides)
+}
+
+// Visits the vertices in maximum-adjacency order and calls `visit(v, w, q)` for every edge scanned
+// from `v` to an unvisited `w`, where `q` is the adjacency of `w` to the visited vertices.
+pub(crate) fn maximum_adjacency_scan<W: Weight>(
+    graph: &Graph<W>,
+    mut visit: impl FnMut(usize, usize, W),
+) {
+    let n = graph.num_vertices();
+    let mut keys = vec![W::ZERO; n];
+    let mut visited = vec![false; n];
+    let mut heap = (0..n)
+        .map(|v| (OrdWeight(W::ZERO), Reverse(v)))
+        .collect::<BinaryHeap<_>>();
+
+    while let Some((OrdWeight(key), Reverse(v))) = heap.pop() {
+        if visited[v] || key != keys[v] {
+            continue;
+        }
+        visited[v] = true;
+        for edge in graph.adjacency_list()[v].edges.iter() {
+            let w = edge.other;
+            if !visited[w] {
+                keys[w] += edge.weight;
+                visit(v, w, keys[w]);
+                heap.push((OrdWeight(keys[w]), Reverse(w)));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data_structures::graph::Graph;
+    use crate::min_cut_algo::MinCutAlgo;
+    use crate::nagamochi_ibaraki::{MatulaAlgo, NagamochiIbarakiAlgo};
+    use crate::stoer_wagner::StoerWagnerAlgo;
+    use crate::traits::EdgeWeightedGraph;
+
+    #[test]
+    fn nagamochi_ibaraki_sanity_test() {
+        use crate::data_structures::api::DirectedEdge;
+
+        let edges = [
+            (0, 1, 1),
+            (0, 1, 2),
+            (0, 2, 1),
+            (1, 0, 2),
+            (1, 2, 5),
+            (1, 2, 3),
+            (2, 1, 3),
+            (3, 1, 8),
+            (3, 2, 5),
+        ][..]
+            .iter()
+            .map(|it| DirectedEdge::from(*it));
+        let graph: Graph = Graph::from_directed_edges(4, edges).unwrap();
+
+        let min_cut = NagamochiIbarakiAlgo::new(&graph).execute(false).unwrap();
+        assert_eq!(min_cut.value(), 6);
+        assert_eq!(min_cut.partition(), (&[0][..], &[1, 2, 3][..]));
+
+        let approx_min_cut = MatulaAlgo::new(&graph, 0.5).execute(false).unwrap();
+        assert!(approx_min_cut.value() >= 6);
+        assert!(approx_min_cut.value() as f64 <= 2.5 * 6f64);
+    }
+
+    #[test]
+    fn nagamochi_ibaraki_test_files() {
+        for input in [
+            "src/files/input_random_1_6.txt",
+            "src/files/input_random_10_25.txt",
+            "src/files/input_random_20_75.txt",
+            "src/files/input_random_40_200.txt",
+        ] {
+            let graph = crate::utils::read_graph(input).unwrap();
+            let expected_min_cut = StoerWagnerAlgo::new(&graph).execute(false).unwrap().value();
+
+            let min_cut = NagamochiIbarakiAlgo::new(&graph).execute(false).unwrap();
+            assert_eq!(min_cut.value(), expected_min_cut, "{input}");
+
+            for epsilon in [0.1, 1.0] {
+                let approx_min_cut = MatulaAlgo::new(&graph, epsilon).execute(false).unwrap();
+                assert!(approx_min_cut.value() >= expected_min_cut, "{input}");
+                assert!(
+                    approx_min_cut.value() as f64 <= (2f64 + epsilon) * expected_min_cut as f64,
+                    "{input}"
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+use rand::rngs::StdRng;
+
+use crate::data_structures::graph::Graph;
+use crate::min_cut_algo::{MinCutAlgo, MinCutResult, TrialPlanning};
+use crate::traits::EdgeWeightedGraph;
+use crate::weight::{OrdWeight, Weight};
+
+pub struct StoerWagnerAlgo<'a, W = usize> {
+    graph: &'a Graph<W>,
+}
+
+impl<'a, W: Weight> StoerWagnerAlgo<'a, W> {
+    pub fn new(graph: &'a Graph<W>) -> StoerWagnerAlgo<'a, W> {
+        Self { graph }
+    }
+
+    fn min_cut(&self) -> MinCutResult<W> {
+        let n = self.graph.num_vertices();
+
+        let mut adj = vec![HashMap::<usize, W>::new(); n];
+        for node in self.graph.adjacency_list() {
+            for edge in node.edges.iter() {
+                *adj[node.vertex].entry(edge.other).or_insert(W::ZERO) += edge.weight;
+                *adj[edge.other].entry(node.vertex).or_insert(W::ZERO) += edge.weight;
+            }
+        }
+
+        let mut members = (0..n).map(|v| vec![v]).collect::<Vec<_>>();
+        let mut active = (0..n).collect::<Vec<_>>();
+        let mut best = W::MAX;
+        let mut best_members = Vec::new();
+
+        while active.len() > 1 {
+            let (s, t, cut_of_phase) = Self::maximum_adjacency_phase(&adj, &active);
+
+            if cut_of_phase < best {
+                best = cut_of_phase;
+                best_members = members[t].clone();
+            }
+
+            let t_edges = std::mem::take(&mut adj[t]);
+            for (x, weight) in t_edges {
+                adj[x].remove(&t);
+                if x != s {
+                    *adj[s].entry(x).or_insert(W::ZERO) += weight;
+                    *adj[x].entry(s).or_insert(W::ZERO) += weight;
+                }
+            }
+            let t_members = std::mem::take(&mut members[t]);
+            members[s].extend(t_members);
+            active.retain(|&v| v != t);
+        }
+
+        let mut sides = vec![0; n];
+        for v in best_members {
+            sides[v] = 1;
+        }
+        MinCutResult::from_sides(self.graph, &sides)
+    }
+
+    fn maximum_adjacency_phase(adj: &[HashMap<usize, W>], active: &[usize]) -> (usize, usize, W) {
+        let mut keys = vec![W::ZERO; adj.len()];
+        let mut added = vec![false; adj.len()];
+        let mut heap = active
+            .iter()
+            .map(|&v| (OrdWeight(W::ZERO), Reverse(v)))
+            .collect::<BinaryHeap<_>>();
+
+        let (mut s, mut t) = (active[0], active[0]);
+        while let Some((OrdWeight(key), Reverse(v))) = heap.pop() {
+            if added[v] || key != keys[v] {
+                continue;
+            }
+            added[v] = true;
+            s = t;
+            t = v;
+            for (&x, &weight) in adj[v].iter() {
+                if !added[x] {
+                    keys[x] += weight;
+                    heap.push((OrdWeight(keys[x]), Reverse(x)));
+                }
+            }
+        }
+        (s, t, keys[t])
+    }
+}
+
+impl<'a, W: Weight> TrialPlanning for StoerWagnerAlgo<'a, W> {
+    fn single_trial_fail_prob(&self) -> f64 {
+        0f64
+    }
+}
+
+impl<'a, W: Weight> MinCutAlgo<W> for StoerWagnerAlgo<'a, W> {
+    fn graph(&self) -> &Graph<W> {
+        self.graph
+    }
+
+    fn trial(&self, _rng: &mut StdRng) -> MinCutResult<W> {
+        self.min_cut()
+    }
+
+    fn approx_num_trials(&self, _last_improvement: Option<usize>) -> usize {
+        1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data_structures::graph::Graph;
+    use crate::min_cut_algo::{MinCutAlgo, TrialPlanning};
+    use crate::stoer_wagner::StoerWagnerAlgo;
+    use crate::traits::EdgeWeightedGraph;
+
+    #[test]
+    fn stoer_wagner_sanity_test() {
+        use crate::data_structures::api::DirectedEdge;
+
+        let edges = [
+            (0, 1, 1),
+            (0, 1, 2),
+            (0, 2, 1),
+            (1, 0, 2),
+            (1, 2, 5),
+            (1, 2, 3),
+            (2, 1, 3),
+            (3, 1, 8),
+            (3, 2, 5),
+        ][..]
+            .iter()
+            .map(|it| DirectedEdge::from(*it));
+        let graph: Graph = Graph::from_directed_edges(4, edges).unwrap();
+
+        let stoer_wagner = StoerWagnerAlgo::new(&graph);
+        let min_cut = stoer_wagner.execute(false).unwrap();
+        assert_eq!(min_cut.value(), 6);
+        assert_eq!(min_cut.partition(), (&[0][..], &[1, 2, 3][..]));
+        assert_eq!(stoer_wagner.min_num_trials(0.99), 1);
+        assert_eq!(stoer_wagner.approx_execute(false).unwrap(), min_cut);
+    }
+
+    #[test]
+    fn stoer_wagner_test_files() {
+        for (input, expected_min_cut) in [
+            ("src/files/input_random_1_6.txt", 4),
+            ("src/files/input_random_10_25.txt", 12),
+            ("src/files/input_random_20_75.txt", 32),
+            ("src/files/input_random_40_200.txt", 122),
+        ] {
+            let graph = crate::utils::read_graph(input).unwrap();
+            let min_cut = StoerWagnerAlgo::new(&graph).execute(false).unwrap();
+            assert_eq!(min_cut.value(), expected_min_cut, "{input}");
+        }
+    }
+}
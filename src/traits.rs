@@ -1,16 +1,57 @@
-use crate::data_structures::api::DirectedEdge;
-use crate::data_structures::graph_util::Node;
+use rand::Rng;
+
+use crate::data_structures::api::{DirectedEdge, GraphError};
+use crate::data_structures::contraction::Contraction;
+use crate::weight::Weight;
+
+/// A graph built from weighted arcs.
+pub trait EdgeWeightedGraph {
+    type Weight: Weight;
+
+    /// Fails on an arc with an endpoint out of range, or when twice the total weight overflows,
+    /// see `WeightOverflow`.
+    fn from_directed_edges(
+        n: usize,
+        directed_edges: impl Iterator<Item = DirectedEdge<Self::Weight>>,
+    ) -> Result<Self, GraphError>
+    where
+        Self: Sized;
+
+    /// Every edge is given once, in either direction, and becomes an arc each way.
+    fn from_undirected_edges(
+        n: usize,
+        edges: impl Iterator<Item = DirectedEdge<Self::Weight>>,
+    ) -> Result<Self, GraphError>
+    where
+        Self: Sized,
+    {
+        let directed_edges = edges.flat_map(|edge| {
+            let (v, w, weight) = match edge {
+                DirectedEdge::Unweighted(v, w) => (v, w, Self::Weight::ONE),
+                DirectedEdge::Weighted(v, w, weight) => (v, w, weight),
+            };
+            [
+                DirectedEdge::Weighted(v, w, weight),
+                DirectedEdge::Weighted(w, v, weight),
+            ]
+        });
+        Self::from_directed_edges(n, directed_edges)
+    }
 
-pub(crate) trait EdgeWeightedGraph {
-    fn from_directed_edges(n: usize, directed_edges: impl Iterator<Item = DirectedEdge>) -> Self;
     fn num_vertices(&self) -> usize;
     fn num_edges(&self) -> usize;
-    fn weight(&self) -> usize;
-    fn adjacency_list(&self) -> &[Node];
+    fn weight(&self) -> Self::Weight;
 }
 
-pub(crate) trait MinCutGraph: EdgeWeightedGraph {
-    fn contract_full(&self) -> Self;
+/// Random contraction, merging the endpoints of an edge drawn in proportion to its weight until `t`
+/// vertices are left. Panics unless `2 <= t <= |V|`.
+pub trait MinCutGraph: EdgeWeightedGraph {
+    fn contract_full<R: Rng + ?Sized>(&self, rng: &mut R) -> Self;
+
+    fn contract<R: Rng + ?Sized>(&self, t: usize, rng: &mut R) -> Self;
 
-    fn contract(&self, t: usize) -> Self;
+    /// The contracted graph with the vertex of it each vertex became.
+    fn contract_mapped<R: Rng + ?Sized>(&self, t: usize, rng: &mut R) -> Contraction<Self>
+    where
+        Self: Sized;
 }
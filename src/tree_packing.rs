@@ -0,0 +1,401 @@
+use std::sync::Mutex;
+
+use rand::rngs::StdRng;
+use rand::seq::index::sample;
+use rand::{Rng, SeedableRng};
+
+use crate::data_structures::graph::Graph;
+use crate::data_structures::segment_tree::MinSegmentTree;
+use crate::data_structures::union_find::UnionFind;
+use crate::min_cut_algo::{MinCutAlgo, MinCutResult, TrialPlanning};
+use crate::nagamochi_ibaraki::MatulaAlgo;
+use crate::traits::EdgeWeightedGraph;
+use crate::weight::{SignedWeight, Weight};
+
+const SKELETON_MIN_CUT_FACTOR: f64 = 3f64;
+
+// Karger's near-linear min cut: sample a skeleton whose min cut is O(log |V|), greedily pack
+// spanning trees into it, then find the min cut of the graph that crosses at most two edges of
+// some of the packed trees.
+pub struct TreePackingAlgo<'a, W = usize> {
+    graph: &'a Graph<W>,
+    rng: Mutex<StdRng>,
+}
+
+impl<'a, W: Weight> TreePackingAlgo<'a, W> {
+    pub fn new(graph: &'a Graph<W>) -> TreePackingAlgo<'a, W> {
+        Self::from_rng(graph, StdRng::from_entropy())
+    }
+
+    pub fn with_seed(graph: &'a Graph<W>, seed: u64) -> TreePackingAlgo<'a, W> {
+        Self::from_rng(graph, StdRng::seed_from_u64(seed))
+    }
+
+    fn from_rng(graph: &'a Graph<W>, rng: StdRng) -> TreePackingAlgo<'a, W> {
+        Self {
+            graph,
+            rng: Mutex::new(rng),
+        }
+    }
+
+    fn undirected_edges(&self) -> Vec<(usize, usize, W)> {
+        self.graph
+            .symmetrized()
+            .adjacency_list()
+            .iter()
+            .flat_map(|node| {
+                node.edges
+                    .iter()
+                    .filter(move |edge| node.vertex < edge.other)
+                    .map(move |edge| (node.vertex, edge.other, edge.weight))
+            })
+            .collect()
+    }
+
+    fn pack_trees(
+        n: usize,
+        edges: &[(usize, usize, W)],
+        capacities: &[usize],
+        num_trees: usize,
+    ) -> Vec<Vec<usize>> {
+        let mut loads = vec![0usize; edges.len()];
+        let mut order = (0..edges.len()).collect::<Vec<_>>();
+        let mut trees = Vec::with_capacity(num_trees);
+
+        for _ in 0..num_trees {
+            let relative_load = |i: usize| match capacities[i] {
+                0 => f64::INFINITY,
+                capacity => loads[i] as f64 / capacity as f64,
+            };
+            order.sort_by(|&i, &j| relative_load(i).total_cmp(&relative_load(j)));
+
+            let mut components = UnionFind::new(n);
+            let mut tree = Vec::with_capacity(n - 1);
+            for &i in order.iter() {
+                let (v, w, _) = edges[i];
+                if !components.connected(v, w) {
+                    components.union(v, w);
+                    tree.push(i);
+                    loads[i] += 1;
+                }
+            }
+            trees.push(tree);
+        }
+        trees
+    }
+}
+
+impl<'a, W: Weight> TrialPlanning for TreePackingAlgo<'a, W> {
+    fn trial_rng(&self) -> StdRng {
+        StdRng::seed_from_u64(self.rng.lock().unwrap().gen())
+    }
+
+    fn single_trial_fail_prob(&self) -> f64 {
+        1f64 / self.graph.num_vertices() as f64
+    }
+}
+
+impl<'a, W: Weight> MinCutAlgo<W> for TreePackingAlgo<'a, W> {
+    fn graph(&self) -> &Graph<W> {
+        self.graph
+    }
+
+    fn trial(&self, rng: &mut StdRng) -> MinCutResult<W> {
+        let n = self.graph.num_vertices();
+        let estimate = MatulaAlgo::new(self.graph, 1f64)
+            .iterate()
+            .expect("the entry points check the graph before any trial");
+        if n <= 2 || estimate.value() == W::ZERO {
+            return estimate;
+        }
+
+        let edges = self.undirected_edges();
+
+        // The estimate is within a factor of 3 of the min cut.
+        let ln_n = (n as f64).ln();
+        let min_cut_lower_bound = estimate.value().to_f64() / 3f64;
+        let p = f64::min(1f64, SKELETON_MIN_CUT_FACTOR * ln_n / min_cut_lower_bound);
+        let capacities = edges
+            .iter()
+            .map(|&(_, _, weight)| {
+                let expected = weight.to_f64() * p;
+                expected.floor() as usize + rng.gen_bool(expected.fract()) as usize
+            })
+            .collect::<Box<[usize]>>();
+
+        let skeleton_min_cut = (estimate.value().to_f64() * p).ceil();
+        let num_trees = (skeleton_min_cut * ln_n).ceil() as usize;
+        let trees = Self::pack_trees(n, &edges, &capacities, num_trees);
+
+        let num_sampled_trees = std::cmp::min(trees.len(), ln_n.ceil() as usize + 1);
+        let mut min_cut = estimate;
+        for i in sample(rng, trees.len(), num_sampled_trees) {
+            let tree = trees[i].iter().map(|&e| edges[e]);
+            let (value, sides) = TwoRespectingCuts::new(n, tree).min_cut(&edges);
+            if value < min_cut.value() {
+                min_cut = MinCutResult::from_sides(self.graph, &sides);
+            }
+        }
+        min_cut
+    }
+
+    fn approx_num_trials(&self, last_improvement: Option<usize>) -> usize {
+        match last_improvement {
+            None => 1,
+            Some(i) => i + 1,
+        }
+    }
+}
+
+// Heavy-path decomposition of a spanning tree rooted at vertex 0. Vertices are numbered in a
+// pre-order that visits heavy children first, so both subtrees and heavy paths are contiguous
+// ranges of positions. Every non-root vertex stands for the tree edge to its parent.
+struct TwoRespectingCuts {
+    parent: Box<[usize]>,
+    depth: Box<[usize]>,
+    size: Box<[usize]>,
+    head: Box<[usize]>,
+    pos: Box<[usize]>,
+    order: Box<[usize]>,
+}
+
+impl TwoRespectingCuts {
+    fn new<W>(n: usize, tree: impl Iterator<Item = (usize, usize, W)>) -> Self {
+        let mut children = vec![Vec::new(); n];
+        for (v, w, _) in tree {
+            children[v].push(w);
+            children[w].push(v);
+        }
+
+        let mut parent = vec![0; n].into_boxed_slice();
+        let mut depth = vec![0; n].into_boxed_slice();
+        let mut preorder = Vec::with_capacity(n);
+        let mut stack = vec![0];
+        let mut visited = vec![false; n];
+        visited[0] = true;
+        while let Some(v) = stack.pop() {
+            preorder.push(v);
+            for &w in children[v].iter() {
+                if !visited[w] {
+                    visited[w] = true;
+                    parent[w] = v;
+                    depth[w] = depth[v] + 1;
+                    stack.push(w);
+                }
+            }
+        }
+        for v in 0..n {
+            let p = parent[v];
+            children[v].retain(|&w| w != p);
+        }
+
+        let mut size = vec![1; n].into_boxed_slice();
+        for &v in preorder.iter().skip(1).rev() {
+            size[parent[v]] += size[v];
+        }
+
+        let mut head = vec![0; n].into_boxed_slice();
+        let mut pos = vec![0; n].into_boxed_slice();
+        let mut order = vec![0; n].into_boxed_slice();
+        let mut stack = vec![0];
+        let mut next = 0;
+        while let Some(v) = stack.pop() {
+            pos[v] = next;
+            order[next] = v;
+            next += 1;
+            let heavy = children[v].iter().copied().max_by_key(|&w| size[w]);
+            for &w in children[v].iter() {
+                if Some(w) != heavy {
+                    head[w] = w;
+                    stack.push(w);
+                }
+            }
+            if let Some(w) = heavy {
+                head[w] = head[v];
+                stack.push(w);
+            }
+        }
+
+        TwoRespectingCuts {
+            parent,
+            depth,
+            size,
+            head,
+            pos,
+            order,
+        }
+    }
+
+    // Position ranges of the tree edges on the path between `v` and `w`, and their lowest common
+    // ancestor.
+    fn path(&self, mut v: usize, mut w: usize) -> (Vec<(usize, usize)>, usize) {
+        let mut ranges = Vec::new();
+        while self.head[v] != self.head[w] {
+            if self.depth[self.head[v]] < self.depth[self.head[w]] {
+                std::mem::swap(&mut v, &mut w);
+            }
+            ranges.push((self.pos[self.head[v]], self.pos[v]));
+            v = self.parent[self.head[v]];
+        }
+        if self.depth[v] > self.depth[w] {
+            std::mem::swap(&mut v, &mut w);
+        }
+        if v != w {
+            ranges.push((self.pos[v] + 1, self.pos[w]));
+        }
+        (ranges, v)
+    }
+
+    fn in_subtree(&self, v: usize, root: usize) -> bool {
+        self.pos[root] <= self.pos[v] && self.pos[v] < self.pos[root] + self.size[root]
+    }
+
+    // The min cut, over all cuts that cross one or two tree edges, with the side of every vertex.
+    // A graph edge crosses the cut of tree edges `e` and `f` iff its tree path contains exactly one
+    // of them, so the cut weighs `C(e) + C(f) - 2 * cov(e, f)`, where `C(e)` is the weight of the
+    // edges whose path contains `e` and `cov(e, f)` of those whose path contains both. Sweeping `e`
+    // along the heavy paths, every graph edge is added to and removed from the segment tree once
+    // per heavy path it meets.
+    fn min_cut<W: Weight>(&self, edges: &[(usize, usize, W)]) -> (W, Box<[usize]>) {
+        let n = self.order.len();
+        let mut starts = vec![Vec::new(); n];
+        let mut ends = vec![Vec::new(); n];
+        let mut paths = Vec::with_capacity(edges.len());
+        let mut crossing = vec![W::Signed::ZERO; n];
+
+        for (i, &(v, w, weight)) in edges.iter().enumerate() {
+            let (ranges, lca) = self.path(v, w);
+            let weight = weight.to_signed();
+            crossing[v] += weight;
+            crossing[w] += weight;
+            crossing[lca] -= weight + weight;
+            for &(l, r) in ranges.iter() {
+                starts[l].push(i);
+                ends[r].push(i);
+            }
+            paths.push(ranges);
+        }
+        for &v in self.order.iter().skip(1).rev() {
+            let c = crossing[v];
+            crossing[self.parent[v]] += c;
+        }
+
+        let values = self
+            .order
+            .iter()
+            .map(|&v| crossing[v])
+            .collect::<Box<[_]>>();
+        let mut tree = MinSegmentTree::from(&values);
+
+        let (mut best, mut best_pair) = (W::Signed::MAX, (0, 0));
+        for p in 1..n {
+            for &i in starts[p].iter() {
+                let weight = edges[i].2.to_signed();
+                for &(l, r) in paths[i].iter() {
+                    tree.add(l, r, -(weight + weight));
+                }
+            }
+
+            let c = crossing[self.order[p]];
+            if c < best {
+                (best, best_pair) = (c, (p, p));
+            }
+            let (left, right) = (tree.min(1, p - 1), tree.min(p + 1, n - 1));
+            let (other, q) = if right.0 < left.0 { right } else { left };
+            if other != W::Signed::MAX && c + other < best {
+                (best, best_pair) = (c + other, (p, q));
+            }
+
+            for &i in ends[p].iter() {
+                let weight = edges[i].2.to_signed();
+                for &(l, r) in paths[i].iter() {
+                    tree.add(l, r, weight + weight);
+                }
+            }
+        }
+
+        let (e, f) = (self.order[best_pair.0], self.order[best_pair.1]);
+        let sides = (0..n)
+            .map(|v| {
+                let (in_e, in_f) = (self.in_subtree(v, e), self.in_subtree(v, f));
+                (in_e != (in_f && e != f)) as usize
+            })
+            .collect();
+        (W::from_signed(best), sides)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data_structures::graph::Graph;
+    use crate::min_cut_algo::MinCutAlgo;
+    use crate::stoer_wagner::StoerWagnerAlgo;
+    use crate::traits::EdgeWeightedGraph;
+    use crate::tree_packing::{TreePackingAlgo, TwoRespectingCuts};
+
+    #[test]
+    fn two_respecting_cuts() {
+        // Path 0 - 1 - 2 - 3 - 4 with a heavy chord between 1 and 3.
+        let tree = [(0, 1, 1), (1, 2, 1), (2, 3, 1), (3, 4, 1)];
+        let edges: [(usize, usize, usize); 5] =
+            [(0, 1, 5), (1, 2, 1), (2, 3, 1), (3, 4, 5), (1, 3, 5)];
+        let cuts = TwoRespectingCuts::new(5, tree.into_iter());
+        let (value, sides) = cuts.min_cut(&edges);
+        assert_eq!(value, 2);
+        assert_eq!(sides[..], [0, 0, 1, 0, 0]);
+
+        let edges = [
+            (0, 1, 5.0),
+            (1, 2, 3.0),
+            (2, 3, 0.5),
+            (3, 4, 5.0),
+            (0, 4, 1.5),
+        ];
+        let (value, sides) = cuts.min_cut(&edges);
+        assert_eq!(value, 2.0);
+        assert_eq!(sides[..], [0, 0, 0, 1, 1]);
+    }
+
+    #[test]
+    fn tree_packing_sanity_test() {
+        use crate::data_structures::api::DirectedEdge;
+
+        let edges = [
+            (0, 1, 1),
+            (0, 1, 2),
+            (0, 2, 1),
+            (1, 0, 2),
+            (1, 2, 5),
+            (1, 2, 3),
+            (2, 1, 3),
+            (3, 1, 8),
+            (3, 2, 5),
+        ][..]
+            .iter()
+            .map(|it| DirectedEdge::from(*it));
+        let graph: Graph = Graph::from_directed_edges(4, edges).unwrap();
+
+        let min_cut = TreePackingAlgo::with_seed(&graph, 3)
+            .execute(false)
+            .unwrap();
+        assert_eq!(min_cut.value(), 6);
+        assert_eq!(min_cut.partition(), (&[0][..], &[1, 2, 3][..]));
+    }
+
+    #[test]
+    fn tree_packing_test_files() {
+        for input in [
+            "src/files/input_random_10_25.txt",
+            "src/files/input_random_20_75.txt",
+            "src/files/input_random_40_200.txt",
+        ] {
+            let graph = crate::utils::read_graph(input).unwrap();
+            let expected_min_cut = StoerWagnerAlgo::new(&graph).execute(false).unwrap().value();
+
+            let min_cut = TreePackingAlgo::with_seed(&graph, 1)
+                .execute(false)
+                .unwrap();
+            assert_eq!(min_cut.value(), expected_min_cut, "{input}");
+        }
+    }
+}
@@ -1,40 +1,453 @@
+use std::collections::HashSet;
+use std::fmt::{Debug, Display, Formatter};
 use std::fs::File;
 use std::io::{BufRead, BufReader};
 use std::path::Path;
 
 use crate::data_structures::api::DirectedEdge;
+pub use crate::data_structures::api::{Symmetrize, SymmetrizeError, SymmetryViolation};
 use crate::data_structures::graph::Graph;
-use crate::traits::EdgeWeightedGraph;
-
-pub fn read_graph(path: impl AsRef<Path>) -> Graph {
-    let file = File::open(path).unwrap();
-    let reader = BufReader::new(file);
-    let mut lines = reader.lines();
-
-    let n = lines
-        .by_ref()
-        .take(1)
-        .map(Result::unwrap)
-        .nth(0)
-        .unwrap()
-        .trim()
+use crate::weight::{Weight, WeightOverflow};
+
+#[derive(Debug)]
+pub enum ReadGraphError<W = usize> {
+    Io(std::io::Error),
+    MissingHeader,
+    InvalidToken {
+        line: usize,
+        column: usize,
+        token: String,
+    },
+    MissingEndpoint {
+        line: usize,
+        column: usize,
+    },
+    UnexpectedToken {
+        line: usize,
+        column: usize,
+        token: String,
+    },
+    VertexOutOfRange {
+        line: usize,
+        column: usize,
+        vertex: usize,
+        num_vertices: usize,
+    },
+    VertexCountMismatch {
+        declared: usize,
+        used: usize,
+    },
+    Asymmetric(SymmetryViolation<W>),
+    WeightOverflow(WeightOverflow),
+}
+
+impl<W: Display> Display for ReadGraphError<W> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReadGraphError::Io(err) => write!(f, "I/O error: {err}"),
+            ReadGraphError::MissingHeader => write!(f, "missing vertex count header"),
+            ReadGraphError::InvalidToken {
+                line,
+                column,
+                token,
+            } => write!(f, "{line}:{column}: invalid number {token:?}"),
+            ReadGraphError::MissingEndpoint { line, column } => {
+                write!(f, "{line}:{column}: expected a second vertex")
+            }
+            ReadGraphError::UnexpectedToken {
+                line,
+                column,
+                token,
+            } => write!(f, "{line}:{column}: unexpected token {token:?}"),
+            ReadGraphError::VertexOutOfRange {
+                line,
+                column,
+                vertex,
+                num_vertices,
+            } => write!(
+                f,
+                "{line}:{column}: vertex {vertex} is out of range for a graph of {num_vertices} vertices"
+            ),
+            ReadGraphError::VertexCountMismatch { declared, used } => write!(
+                f,
+                "header declares {declared} vertices but {used} are used by the edges"
+            ),
+            ReadGraphError::Asymmetric(violation) => write!(f, "asymmetric graph: {violation}"),
+            ReadGraphError::WeightOverflow(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl<W: Debug + Display> std::error::Error for ReadGraphError<W> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ReadGraphError::Io(err) => Some(err),
+            ReadGraphError::WeightOverflow(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl<W> From<std::io::Error> for ReadGraphError<W> {
+    fn from(err: std::io::Error) -> Self {
+        ReadGraphError::Io(err)
+    }
+}
+
+impl<W> From<WeightOverflow> for ReadGraphError<W> {
+    fn from(err: WeightOverflow) -> Self {
+        ReadGraphError::WeightOverflow(err)
+    }
+}
+
+impl<W> From<SymmetrizeError<W>> for ReadGraphError<W> {
+    fn from(err: SymmetrizeError<W>) -> Self {
+        match err {
+            SymmetrizeError::Asymmetric(violation) => ReadGraphError::Asymmetric(violation),
+            SymmetrizeError::WeightOverflow(err) => ReadGraphError::WeightOverflow(err),
+        }
+    }
+}
+
+/// How `read_graph_with` checks and transforms the graph it reads.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ReadOptions {
+    /// Rejects a header whose vertex count doesn't match the vertices used by the edges.
+    pub strict: bool,
+    /// Makes the graph undirected, the arcs being kept as they are otherwise.
+    pub symmetrize: Option<Symmetrize>,
+}
+
+pub fn read_graph(path: impl AsRef<Path>) -> Result<Graph, ReadGraphError> {
+    read_graph_with(path, ReadOptions::default())
+}
+
+pub fn read_graph_with(
+    path: impl AsRef<Path>,
+    options: ReadOptions,
+) -> Result<Graph, ReadGraphError> {
+    read_weighted_graph(path, options)
+}
+
+/// Reads a graph with weights of any type, such as fractional capacities into a `Graph<f64>`.
+pub fn read_weighted_graph<W: Weight>(
+    path: impl AsRef<Path>,
+    options: ReadOptions,
+) -> Result<Graph<W>, ReadGraphError<W>> {
+    let file = File::open(path)?;
+    parse_graph(BufReader::new(file), options)
+}
+
+pub fn parse_graph<W: Weight>(
+    reader: impl BufRead,
+    options: ReadOptions,
+) -> Result<Graph<W>, ReadGraphError<W>> {
+    let mut lines = reader
+        .lines()
+        .enumerate()
+        .map(|(i, line)| line.map(|line| (i + 1, line)))
+        .filter(|line| {
+            line.as_ref().map_or(true, |(_, line)| {
+                let line = line.trim_start();
+                !(line.is_empty() || line.starts_with('#') || line.starts_with('%'))
+            })
+        });
+
+    let (line_number, header) = lines.next().ok_or(ReadGraphError::MissingHeader)??;
+    let mut header = tokens(line_number, &header);
+    let n = match header.next() {
+        Some(token) => parse_token(token)?,
+        None => return Err(ReadGraphError::MissingHeader),
+    };
+    if let Some(token) = header.next() {
+        return Err(unexpected_token(token));
+    }
+
+    let mut directed_edges = Vec::new();
+    for line in lines {
+        let (line_number, line) = line?;
+        let mut t = tokens(line_number, &line);
+
+        let parse_vertex = |t: Option<Token>| match t {
+            Some(token) => {
+                let vertex = parse_token(token)?;
+                if vertex < n {
+                    Ok(vertex)
+                } else {
+                    Err(ReadGraphError::VertexOutOfRange {
+                        line: token.line,
+                        column: token.column,
+                        vertex,
+                        num_vertices: n,
+                    })
+                }
+            }
+            None => Err(ReadGraphError::MissingEndpoint {
+                line: line_number,
+                column: line.trim_end().chars().count() + 1,
+            }),
+        };
+        let v = parse_vertex(t.next())?;
+        let w = parse_vertex(t.next())?;
+        let weight = t.next().map(parse_weight).transpose()?.unwrap_or(W::ONE);
+        if let Some(token) = t.next() {
+            return Err(unexpected_token(token));
+        }
+        directed_edges.push(DirectedEdge::Weighted(v, w, weight));
+    }
+
+    if options.strict {
+        let used = directed_edges
+            .iter()
+            .flat_map(|edge| match *edge {
+                DirectedEdge::Unweighted(v, w) | DirectedEdge::Weighted(v, w, _) => [v, w],
+            })
+            .collect::<HashSet<usize>>()
+            .len();
+        if used != n {
+            return Err(ReadGraphError::VertexCountMismatch { declared: n, used });
+        }
+    }
+
+    let graph = Graph::from_checked_edges(n, directed_edges.into_iter())?;
+    match options.symmetrize {
+        Some(policy) => Ok(graph.symmetrize(policy)?),
+        None => Ok(graph),
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Token<'a> {
+    line: usize,
+    column: usize,
+    text: &'a str,
+}
+
+fn tokens(line_number: usize, line: &str) -> impl Iterator<Item = Token<'_>> {
+    line.split_whitespace().map(move |text| {
+        let offset = text.as_ptr() as usize - line.as_ptr() as usize;
+        Token {
+            line: line_number,
+            column: line[..offset].chars().count() + 1,
+            text,
+        }
+    })
+}
+
+fn parse_token<W>(token: Token) -> Result<usize, ReadGraphError<W>> {
+    token
+        .text
         .parse::<usize>()
+        .map_err(|_| invalid_token(token))
+}
+
+// Weights are finite and non-negative.
+fn parse_weight<W: Weight>(token: Token) -> Result<W, ReadGraphError<W>> {
+    match token.text.parse::<W>() {
+        Ok(weight) if weight >= W::ZERO && weight.is_finite() => Ok(weight),
+        _ => Err(invalid_token(token)),
+    }
+}
+
+fn invalid_token<W>(token: Token) -> ReadGraphError<W> {
+    ReadGraphError::InvalidToken {
+        line: token.line,
+        column: token.column,
+        token: token.text.to_string(),
+    }
+}
+
+fn unexpected_token<W>(token: Token) -> ReadGraphError<W> {
+    ReadGraphError::UnexpectedToken {
+        line: token.line,
+        column: token.column,
+        token: token.text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data_structures::api::{Symmetrize, SymmetryViolation};
+    use crate::data_structures::graph::Graph;
+    use crate::traits::EdgeWeightedGraph;
+    use crate::utils::{parse_graph, read_graph, ReadGraphError, ReadOptions};
+
+    fn parse(input: &str, strict: bool) -> Result<Graph, ReadGraphError> {
+        parse_graph(
+            input.as_bytes(),
+            ReadOptions {
+                strict,
+                ..ReadOptions::default()
+            },
+        )
+    }
+
+    fn parse_symmetrized(input: &str, policy: Symmetrize) -> Result<Graph, ReadGraphError> {
+        parse_graph(
+            input.as_bytes(),
+            ReadOptions {
+                symmetrize: Some(policy),
+                ..ReadOptions::default()
+            },
+        )
+    }
+
+    #[test]
+    fn parse_valid_graphs() {
+        let graph = parse(
+            "# comment\n3\n\n0 1 2\n\n% other comment\n1 0\n  1 2 4 \n",
+            false,
+        )
         .unwrap();
+        assert_eq!(graph.num_vertices(), 3);
+        assert_eq!(graph.num_edges(), 3);
+        assert_eq!(graph.weight(), 7);
 
-    let parse_directed_edge = |line: String| {
-        let mut t = line
-            .trim()
-            .split_whitespace()
-            .map(str::parse::<usize>)
-            .map(Result::unwrap)
-            .take(3);
-        let v = t.next().unwrap();
-        let w = t.next().unwrap();
-        let weight = t.next().unwrap_or(1);
-        DirectedEdge::Weighted(v, w, weight)
-    };
+        let graph = parse("3\n0 1\n1 2\n", true).unwrap();
+        assert_eq!(graph.num_vertices(), 3);
+
+        let graph = read_graph("src/files/input_random_1_6.txt").unwrap();
+        assert_eq!(graph.num_vertices(), 6);
+
+        let graph: Graph<f64> =
+            parse_graph("2\n0 1 0.5\n1 0 1.25\n".as_bytes(), ReadOptions::default()).unwrap();
+        assert_eq!(graph.weight(), 1.75);
+        for input in ["2\n0 1 -1\n", "2\n0 1 NaN\n", "2\n0 1 inf\n"] {
+            assert!(matches!(
+                parse_graph::<f64>(input.as_bytes(), ReadOptions::default()),
+                Err(ReadGraphError::InvalidToken {
+                    line: 2,
+                    column: 5,
+                    ..
+                })
+            ));
+        }
+    }
+
+    #[test]
+    fn parse_symmetrized_graphs() {
+        let input = "3\n0 1 2\n1 0 3\n1 2 4\n";
+        let graph = parse_symmetrized(input, Symmetrize::Sum).unwrap();
+        assert_eq!(graph.weight(), 18);
+        assert!(graph.symmetry_violations().is_empty());
+
+        let graph = parse_symmetrized(input, Symmetrize::Max).unwrap();
+        assert_eq!(graph.weight(), 14);
+        assert!(graph.symmetry_violations().is_empty());
+
+        assert!(matches!(
+            parse_symmetrized(input, Symmetrize::RequireEqual),
+            Err(ReadGraphError::Asymmetric(
+                SymmetryViolation::WeightMismatch {
+                    v: 0,
+                    w: 1,
+                    forward: 2,
+                    backward: 3
+                }
+            ))
+        ));
+        let graph = parse_symmetrized("3\n0 1 2\n1 0 2\n", Symmetrize::RequireEqual).unwrap();
+        assert_eq!(graph.weight(), 4);
 
-    let directed_edges = lines.map(Result::unwrap).map(parse_directed_edge);
+        let graph = read_graph("src/files/input_random_1_6.txt").unwrap();
+        assert!(graph.symmetry_violations().is_empty());
+        assert_eq!(
+            parse(input, false).unwrap().symmetry_violations(),
+            [
+                SymmetryViolation::WeightMismatch {
+                    v: 0,
+                    w: 1,
+                    forward: 2,
+                    backward: 3
+                },
+                SymmetryViolation::MissingReverse {
+                    from: 1,
+                    to: 2,
+                    weight: 4
+                }
+            ]
+        );
+    }
 
-    Graph::from_directed_edges(n, directed_edges)
+    #[test]
+    fn parse_invalid_graphs() {
+        assert!(matches!(
+            parse("", false),
+            Err(ReadGraphError::MissingHeader)
+        ));
+        assert!(matches!(
+            parse("# only a comment\n\n", false),
+            Err(ReadGraphError::MissingHeader)
+        ));
+        assert!(matches!(
+            parse("3 4\n", false),
+            Err(ReadGraphError::UnexpectedToken {
+                line: 1,
+                column: 3,
+                ..
+            })
+        ));
+        assert!(matches!(
+            parse("x\n", false),
+            Err(ReadGraphError::InvalidToken {
+                line: 1,
+                column: 1,
+                ..
+            })
+        ));
+        assert!(matches!(
+            parse("3\n\n0 1\n1  y\n", false),
+            Err(ReadGraphError::InvalidToken {
+                line: 4,
+                column: 4,
+                ..
+            })
+        ));
+        assert!(matches!(
+            parse("3\n0 1\n 2\n", false),
+            Err(ReadGraphError::MissingEndpoint { line: 3, column: 3 })
+        ));
+        assert!(matches!(
+            parse("3\n0 1 1 1\n", false),
+            Err(ReadGraphError::UnexpectedToken {
+                line: 2,
+                column: 7,
+                ..
+            })
+        ));
+        assert!(matches!(
+            parse("3\n0 3\n", false),
+            Err(ReadGraphError::VertexOutOfRange {
+                line: 2,
+                column: 3,
+                vertex: 3,
+                num_vertices: 3
+            })
+        ));
+        assert!(matches!(
+            parse("4\n0 1\n1 2\n", true),
+            Err(ReadGraphError::VertexCountMismatch {
+                declared: 4,
+                used: 3
+            })
+        ));
+        assert!(matches!(
+            read_graph("src/files/does_not_exist.txt"),
+            Err(ReadGraphError::Io(_))
+        ));
+        assert!(matches!(
+            parse_graph::<u32>("2\n0 1 3000000000\n".as_bytes(), ReadOptions::default()),
+            Err(ReadGraphError::WeightOverflow(_))
+        ));
+        assert!(matches!(
+            parse_graph::<u32>(
+                "2\n0 1 1500000000\n".as_bytes(),
+                ReadOptions {
+                    symmetrize: Some(Symmetrize::Sum),
+                    ..ReadOptions::default()
+                }
+            ),
+            Err(ReadGraphError::WeightOverflow(_))
+        ));
+    }
 }
@@ -0,0 +1,258 @@
+use std::cmp::Ordering;
+use std::fmt::{Debug, Display, Formatter};
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+use rand::Rng;
+
+/// The weight of an edge: an unsigned integer or a non-negative floating-point number.
+pub trait Weight:
+    Copy
+    + Debug
+    + Display
+    + FromStr
+    + PartialOrd
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + AddAssign
+    + SubAssign
+    + Sum
+    + Send
+    + Sync
+    + 'static
+{
+    const ZERO: Self;
+    const ONE: Self;
+    /// Larger than any sum of weights, infinite for floating-point weights.
+    const MAX: Self;
+    /// Whether sums and differences of weights are exact, as for integers but not floating-point
+    /// numbers.
+    const EXACT: bool;
+
+    /// A type that also holds the differences of sums of weights.
+    type Signed: SignedWeight;
+
+    /// The sum, unless it overflows or, for floating-point weights, is not finite.
+    fn checked_add(self, other: Self) -> Option<Self>;
+
+    fn to_f64(self) -> f64;
+
+    fn to_signed(self) -> Self::Signed;
+
+    fn from_signed(value: Self::Signed) -> Self;
+
+    fn total_cmp(&self, other: &Self) -> Ordering;
+
+    fn is_finite(self) -> bool {
+        true
+    }
+
+    /// Whether two sums of weights taken in different orders may stand for the same value: equal
+    /// integers, or floating-point numbers within a relative rounding tolerance.
+    fn approx_eq(self, other: Self) -> bool {
+        self == other
+    }
+
+    /// A random weight in (0, total], such that the weights of a sum are hit in proportion.
+    fn sample<R: Rng + ?Sized>(rng: &mut R, total: Self) -> Self;
+}
+
+pub trait SignedWeight:
+    Copy
+    + Debug
+    + PartialOrd
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Neg<Output = Self>
+    + AddAssign
+    + SubAssign
+    + Send
+    + Sync
+    + 'static
+{
+    const ZERO: Self;
+    const MAX: Self;
+
+    fn saturating_add(self, other: Self) -> Self;
+}
+
+macro_rules! integer_weight {
+    ($($weight:ty),*) => {$(
+        impl Weight for $weight {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+            const MAX: Self = <$weight>::MAX;
+            const EXACT: bool = true;
+
+            type Signed = i128;
+
+            fn checked_add(self, other: Self) -> Option<Self> {
+                <$weight>::checked_add(self, other)
+            }
+
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+
+            fn to_signed(self) -> i128 {
+                self as i128
+            }
+
+            fn from_signed(value: i128) -> Self {
+                value as Self
+            }
+
+            fn total_cmp(&self, other: &Self) -> Ordering {
+                self.cmp(other)
+            }
+
+            fn sample<R: Rng + ?Sized>(rng: &mut R, total: Self) -> Self {
+                rng.gen_range(0..total) + 1
+            }
+        }
+    )*};
+}
+
+integer_weight!(u32, u64, u128, usize);
+
+macro_rules! float_weight {
+    ($($weight:ty),*) => {$(
+        impl Weight for $weight {
+            const ZERO: Self = 0.0;
+            const ONE: Self = 1.0;
+            const MAX: Self = <$weight>::INFINITY;
+            const EXACT: bool = false;
+
+            type Signed = Self;
+
+            fn checked_add(self, other: Self) -> Option<Self> {
+                Some(self + other).filter(|sum| sum.is_finite())
+            }
+
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+
+            fn to_signed(self) -> Self {
+                self
+            }
+
+            fn from_signed(value: Self) -> Self {
+                value
+            }
+
+            fn total_cmp(&self, other: &Self) -> Ordering {
+                <$weight>::total_cmp(self, other)
+            }
+
+            fn is_finite(self) -> bool {
+                <$weight>::is_finite(self)
+            }
+
+            fn approx_eq(self, other: Self) -> bool {
+                let tolerance = <$weight>::EPSILON.sqrt() * self.abs().max(other.abs());
+                (self - other).abs() <= tolerance
+            }
+
+            // `1 - u` for a uniform `u` in [0, 1) never makes the sample 0.
+            fn sample<R: Rng + ?Sized>(rng: &mut R, total: Self) -> Self {
+                total * (1.0 - rng.gen::<Self>())
+            }
+        }
+
+        impl SignedWeight for $weight {
+            const ZERO: Self = 0.0;
+            const MAX: Self = <$weight>::INFINITY;
+
+            fn saturating_add(self, other: Self) -> Self {
+                self + other
+            }
+        }
+    )*};
+}
+
+float_weight!(f32, f64);
+
+macro_rules! signed_integer_weight {
+    ($($weight:ty),*) => {$(
+        impl SignedWeight for $weight {
+            const ZERO: Self = 0;
+            const MAX: Self = <$weight>::MAX;
+
+            fn saturating_add(self, other: Self) -> Self {
+                <$weight>::saturating_add(self, other)
+            }
+        }
+    )*};
+}
+
+signed_integer_weight!(i64, i128);
+
+/// The weights of a graph add up to more than its weight type holds. The undirected algorithms
+/// count every arc in both directions, so twice the total weight must fit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WeightOverflow;
+
+impl Display for WeightOverflow {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "twice the total weight overflows the weight type")
+    }
+}
+
+impl std::error::Error for WeightOverflow {}
+
+// Orders weights totally, so that floating-point weights can key heaps and sorts.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct OrdWeight<W>(pub W);
+
+impl<W: Weight> PartialEq for OrdWeight<W> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<W: Weight> Eq for OrdWeight<W> {}
+
+impl<W: Weight> PartialOrd for OrdWeight<W> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<W: Weight> Ord for OrdWeight<W> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use crate::weight::{OrdWeight, Weight};
+
+    #[test]
+    fn sample_weights() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..1000 {
+            let r = u32::sample(&mut rng, 3);
+            assert!((1..=3).contains(&r));
+            let r = f64::sample(&mut rng, 0.5);
+            assert!(r > 0.0 && r <= 0.5);
+        }
+
+        let mut weights = [2.5, 0.5, 1.0].map(OrdWeight);
+        weights.sort();
+        assert_eq!(weights.map(|w| w.0), [0.5, 1.0, 2.5]);
+        assert!(!f64::NAN.is_finite() && Weight::is_finite(3u64));
+
+        assert_eq!(Weight::checked_add(u32::MAX - 1, 1), Some(u32::MAX));
+        assert_eq!(Weight::checked_add(u32::MAX, 1), None);
+        assert_eq!(Weight::checked_add(f64::MAX, f64::MAX), None);
+
+        assert!((0.1 + 0.2f64).approx_eq(0.3) && !0.3f64.approx_eq(0.3001));
+        assert!(3u32.approx_eq(3) && !3u32.approx_eq(4));
+    }
+}